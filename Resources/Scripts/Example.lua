@@ -0,0 +1,13 @@
+-- Opens the wall at (8, 0) once the player has walked into the top half of the first screen.
+-- Add "script": "Resources/Scripts/Example.lua" to a level in Resources/Campaign.json to try it.
+
+opened = false
+
+function onStep()
+	local x, y = game.playerPosition()
+	if not opened and y < 150 then
+		game.spawnTile(8, 0, 0, "None")
+		game.playSound("GateSpawn")
+		opened = true
+	end
+end
@@ -0,0 +1,92 @@
+use sdl2::rect::{Point, Rect};
+
+/// Fraction of the view the target can move in before the camera follows.
+const DEFAULT_DEAD_ZONE: (f32, f32) = (0.25, 0.25);
+
+/// View into the active screen. Follows a target once it leaves the dead zone in the
+/// middle of the view and never shows anything outside the screen.
+pub struct Camera {
+	position: Point,
+	size: (u32, u32),
+	deadZone: (u32, u32),
+	screen: Option<usize>,
+}
+
+impl Camera {
+	pub fn new(width: u32, height: u32) -> Camera {
+		Camera {
+			position: Point::new(0, 0),
+			size: (width, height),
+			deadZone: ((width as f32 * DEFAULT_DEAD_ZONE.0) as u32, (height as f32 * DEFAULT_DEAD_ZONE.1) as u32),
+			screen: None,
+		}
+	}
+	/// Size in pixels of the area around the center of the view the target can move freely in.
+	pub fn setDeadZone(&mut self, width: u32, height: u32) {
+		self.deadZone = (width.min(self.size.0), height.min(self.size.1));
+	}
+	/// Width and height of the view in pixels.
+	pub fn getSize(&self) -> (u32, u32) {
+		self.size
+	}
+	/// Top left corner of the view in screen coordinates.
+	pub fn getPosition(&self) -> Point {
+		self.position
+	}
+	/// `bounds` is the size of the active screen in pixels. The camera jumps straight to
+	/// the target when `screen` differs from the last call instead of scrolling there.
+	pub fn update(&mut self, target: Point, screen: usize, bounds: (u32, u32)) {
+		if self.screen != Some(screen) {
+			self.screen = Some(screen);
+			self.position = target - Point::new(self.size.0 as i32 / 2, self.size.1 as i32 / 2);
+		}
+		else {
+			let deadZone = Rect::from_center(self.position + Point::new(self.size.0 as i32 / 2, self.size.1 as i32 / 2), self.deadZone.0.max(1), self.deadZone.1.max(1));
+			let dx = (target.x - deadZone.left()).min(0) + (target.x - deadZone.right()).max(0);
+			let dy = (target.y - deadZone.top()).min(0) + (target.y - deadZone.bottom()).max(0);
+			self.position = self.position.offset(dx, dy);
+		}
+		self.position = Point::new(
+			self.position.x.min(bounds.0 as i32 - self.size.0 as i32).max(0),
+			self.position.y.min(bounds.1 as i32 - self.size.1 as i32).max(0),
+		);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const SCREEN: (u32, u32) = (1700, 1200);
+
+	#[test]
+	fn entersCenteredButInsideTheScreen() {
+		let mut camera = Camera::new(850, 600);
+		camera.update(Point::new(900, 700), 0, SCREEN);
+		assert_eq!(camera.getPosition(), Point::new(475, 400));
+		camera.update(Point::new(10, 10), 1, SCREEN);
+		assert_eq!(camera.getPosition(), Point::new(0, 0));
+		camera.update(Point::new(1690, 1190), 2, SCREEN);
+		assert_eq!(camera.getPosition(), Point::new(850, 600));
+	}
+
+	#[test]
+	fn followsOnlyOutsideTheDeadZone() {
+		let mut camera = Camera::new(850, 600);
+		camera.setDeadZone(100, 100);
+		camera.update(Point::new(900, 700), 0, SCREEN);
+		camera.update(Point::new(940, 700), 0, SCREEN);
+		assert_eq!(camera.getPosition(), Point::new(475, 400));
+		camera.update(Point::new(1000, 700), 0, SCREEN);
+		assert_eq!(camera.getPosition(), Point::new(525, 400));
+	}
+
+	#[test]
+	fn staysPutOnScreensNoBiggerThanTheView() {
+		let mut camera = Camera::new(850, 600);
+		camera.update(Point::new(800, 500), 0, (850, 600));
+		assert_eq!(camera.getPosition(), Point::new(0, 0));
+		camera.update(Point::new(20, 580), 0, (850, 600));
+		assert_eq!(camera.getPosition(), Point::new(0, 0));
+	}
+}
@@ -0,0 +1,53 @@
+use serde::{Serialize, Deserialize};
+use serde_json::Deserializer;
+
+use std::io;
+use std::fs::File;
+
+/// Tileset used by levels that do not name their own.
+pub const DEFAULT_TILESET: &str = "Resources/Images/Map1.anim";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LevelEntry {
+	pub name: String,
+	pub file: String,
+	#[serde(default)]
+	pub tileset: Option<String>,
+	#[serde(default)]
+	pub music: Option<String>,
+	/// Lua file run with the `scripting` feature, see `ScriptingMod`
+	#[serde(default)]
+	pub script: Option<String>,
+}
+
+impl LevelEntry {
+	pub fn getTileset(&self) -> &str {
+		self.tileset.as_deref().unwrap_or(DEFAULT_TILESET)
+	}
+}
+
+/// Ordered list of levels. Winning a level moves on to the next entry.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Campaign {
+	levels: Vec<LevelEntry>,
+}
+
+impl Campaign {
+	pub fn load(filename: &str) -> io::Result<Campaign> {
+		let mut deserializer = Deserializer::from_reader(File::open(filename)?);
+		let campaign = Campaign::deserialize(&mut deserializer)?;
+		if campaign.levels.is_empty() {
+			return Err(io::Error::new(io::ErrorKind::InvalidData, "Campaign has no levels"));
+		}
+		Ok(campaign)
+	}
+	pub fn getLevel(&self, idx: usize) -> Option<&LevelEntry> {
+		self.levels.get(idx)
+	}
+	pub fn findLevel(&self, file: &str) -> Option<&LevelEntry> {
+		self.levels.iter().find(|level| level.file == file)
+	}
+	pub fn len(&self) -> usize {
+		self.levels.len()
+	}
+}
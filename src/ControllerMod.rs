@@ -0,0 +1,57 @@
+use sdl2::{Sdl, GameControllerSubsystem};
+use sdl2::controller::{GameController, Button};
+use sdl2::event::Event;
+use sdl2::keyboard::Scancode;
+
+/// Every game controller plugged in. SDL reports the ones present at start up as added too,
+/// so they are all opened through `handleEvent`.
+pub struct Controllers {
+	subsystem: Option<GameControllerSubsystem>,
+	open: Vec<GameController>,
+}
+
+impl Controllers {
+	pub fn new(sdlContext: &Sdl) -> Controllers {
+		let subsystem = sdlContext.game_controller()
+			.map_err(|e| eprintln!("Warning: Game controllers are not available: {}", e)).ok();
+		Controllers {subsystem, open: vec![]}
+	}
+	/// Opens and closes controllers as they are plugged in and out.
+	pub fn handleEvent(&mut self, event: &Event) {
+		match *event {
+			Event::ControllerDeviceAdded{which, ..} => if let Some(ref subsystem) = self.subsystem {
+				match subsystem.open(which) {
+					Ok(controller) => self.open.push(controller),
+					Err(e) => eprintln!("Warning: Could not open controller {}: {}", which, e),
+				}
+			},
+			Event::ControllerDeviceRemoved{which, ..} => self.open.retain(|controller| controller.instance_id() != which),
+			_ => (),
+		}
+	}
+	pub fn get(&self) -> &[GameController] {
+		&self.open
+	}
+	/// The key a controller button stands for in menus: the d-pad moves, A picks, B and Start go back.
+	pub fn menuEvent(event: &Event) -> Option<Event> {
+		let (timestamp, button) = match *event {
+			Event::ControllerButtonDown{timestamp, button, ..} => (timestamp, button),
+			_ => return None,
+		};
+		let scancode = match button {
+			Button::DPadUp => Scancode::Up,
+			Button::DPadDown => Scancode::Down,
+			Button::A => Scancode::Return,
+			Button::B | Button::Start => Scancode::Escape,
+			_ => return None,
+		};
+		Some(Event::KeyDown {
+			timestamp,
+			window_id: 0,
+			keycode: None,
+			scancode: Some(scancode),
+			keymod: sdl2::keyboard::Mod::NOMOD,
+			repeat: false,
+		})
+	}
+}
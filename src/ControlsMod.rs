@@ -0,0 +1,81 @@
+use sdl2::event::Event;
+use sdl2::keyboard::Scancode;
+
+use crate::PlayerMod::{Mapping, Action, ACTIONS};
+
+/// The controls page of the pause menu. Return on an action waits for a key to add to it,
+/// Backspace or Delete unbinds all of its keys. Escape cancels waiting or leaves the page.
+pub struct Rebinding {
+	selected: usize,
+	waiting: bool,
+	done: bool,
+}
+
+/// Row after the actions, the one after it is "Back"
+const RESET: usize = 0;
+
+impl Rebinding {
+	pub fn new() -> Rebinding {
+		Rebinding {selected: 0, waiting: false, done: false}
+	}
+	fn rows(&self) -> usize {
+		ACTIONS.len() + 2
+	}
+	/// Returns whether `mapping` was changed.
+	pub fn handleEvent(&mut self, event: &Event, mapping: &mut Mapping) -> bool {
+		if self.waiting {
+			match event {
+				Event::KeyDown{scancode: Some(Scancode::Escape), repeat: false, ..} => self.waiting = false,
+				Event::KeyDown{scancode: Some(key), repeat: false, ..} => {
+					mapping.bind(ACTIONS[self.selected], *key);
+					self.waiting = false;
+					return true;
+				},
+				_ => (),
+			}
+			return false;
+		}
+		match event {
+			Event::KeyDown{scancode: Some(Scancode::Up), ..} => self.selected = (self.selected + self.rows() - 1) % self.rows(),
+			Event::KeyDown{scancode: Some(Scancode::Down), ..} => self.selected = (self.selected + 1) % self.rows(),
+			Event::KeyDown{scancode: Some(Scancode::Escape), repeat: false, ..} => self.done = true,
+			Event::KeyDown{scancode: Some(Scancode::Return), repeat: false, ..} => match self.selected.checked_sub(ACTIONS.len()) {
+				None => self.waiting = true,
+				Some(RESET) => {
+					*mapping = Mapping::default();
+					return true;
+				},
+				Some(_) => self.done = true,
+			},
+			Event::KeyDown{scancode: Some(Scancode::Backspace | Scancode::Delete), repeat: false, ..} if self.selected < ACTIONS.len() => {
+				mapping.getKeysMut(ACTIONS[self.selected]).clear();
+				return true;
+			},
+			_ => (),
+		}
+		false
+	}
+	/// Whether the next key pressed will be bound.
+	pub fn isWaiting(&self) -> bool {
+		self.waiting
+	}
+	pub fn isDone(&self) -> bool {
+		self.done
+	}
+	/// One line of text per row, plus whether it is highlighted.
+	pub fn lines(&self, mapping: &Mapping) -> Vec<(String, bool)> {
+		let describe = |action: Action| {
+			let keys: Vec<&str> = mapping.getKeys(action).iter().map(|key| key.name()).collect();
+			if keys.is_empty() {String::from("None")} else {keys.join(", ")}
+		};
+		(0..self.rows()).map(|row| {
+			let text = match row.checked_sub(ACTIONS.len()) {
+				None if self.waiting && row == self.selected => format!("{}: Press a key", ACTIONS[row].getName()),
+				None => format!("{}: {}", ACTIONS[row].getName(), describe(ACTIONS[row])),
+				Some(RESET) => String::from("Reset to defaults"),
+				Some(_) => String::from("Back"),
+			};
+			(text, row == self.selected)
+		}).collect()
+	}
+}
@@ -0,0 +1,57 @@
+use sdl2::render::{Canvas, BlendMode};
+use sdl2::video::Window;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+
+use crate::TextMod::TextCache;
+
+const MARGIN: i32 = 20;
+const PADDING: i32 = 15;
+const BOX_HEIGHT: u32 = 150;
+const BOX_COLOR: Color = Color::RGBA(0x10, 0x10, 0x10, 0xe0);
+const TEXT_COLOR: Color = Color::WHITE;
+
+/// Text box shown over the game, one page at a time. Gameplay is paused while it is open.
+pub struct Dialogue {
+	pages: Vec<String>,
+	page: usize,
+}
+
+impl Dialogue {
+	pub fn new(pages: Vec<String>) -> Dialogue {
+		Dialogue {pages, page: 0}
+	}
+	/// Moves to the next page. Returns false once there are no pages left.
+	pub fn advance(&mut self) -> bool {
+		self.page += 1;
+		self.page < self.pages.len()
+	}
+	pub fn draw(&self, canvas: &mut Canvas<Window>, text: &mut TextCache) {
+		let (width, height) = canvas.output_size().unwrap_or((0, 0));
+		let area = Rect::new(MARGIN, height as i32 - BOX_HEIGHT as i32 - MARGIN, width.saturating_sub(2 * MARGIN as u32), BOX_HEIGHT);
+		let (prevColor, prevBlend) = (canvas.draw_color(), canvas.blend_mode());
+		canvas.set_blend_mode(BlendMode::Blend);
+		canvas.set_draw_color(BOX_COLOR);
+		let _ = canvas.fill_rect(area);
+		canvas.set_draw_color(TEXT_COLOR);
+		let _ = canvas.draw_rect(area);
+		canvas.set_draw_color(prevColor);
+		canvas.set_blend_mode(prevBlend);
+
+		let page = self.pages.get(self.page).map_or("", String::as_str);
+		let wrapWidth = area.width().saturating_sub(2 * PADDING as u32);
+		if let Some(texture) = text.get(page, TEXT_COLOR, Some(wrapWidth)) {
+			let query = texture.query();
+			// Lines past the bottom of the box are cut off rather than squashed into it
+			let height = query.height.min(area.height().saturating_sub(2 * PADDING as u32));
+			let _ = canvas.copy(texture, Rect::new(0, 0, query.width, height), Rect::new(area.x() + PADDING, area.y() + PADDING, query.width, height));
+		}
+		if self.pages.len() > 1 {
+			let marker = format!("{}/{}", self.page + 1, self.pages.len());
+			if let Some(texture) = text.get(&marker, TEXT_COLOR, None) {
+				let query = texture.query();
+				let _ = canvas.copy(texture, None, Rect::new(area.right() - PADDING - query.width as i32, area.bottom() - PADDING - query.height as i32, query.width, query.height));
+			}
+		}
+	}
+}
@@ -38,7 +38,7 @@ fn main() {
 
 	let mut ctx = NinjaDungeon::loadCtx(&file, &textureCreator).unwrap_or_else(|_| {
 		eprintln!("Warning: Could not read map file \"{}\"", &file);
-		let mut map = Map::new(0, "Resources/Images/Map1.anim", &textureCreator).unwrap();
+		let mut map = Map::new(0, NinjaDungeon::DEFAULT_TILESET, &textureCreator).unwrap();
 		map.addScreen(17, 12, (0, 0));
 		GameContext::new(map, &textureCreator)
 	});
@@ -220,6 +220,22 @@ impl EditorContext {
 						*deps.fontTexture = Some(createText(&self.message, deps.textureCreator, deps.font));
 					}
 				},
+				(Event::KeyDown {scancode: Some(Scancode::Return), ..}, State::GetUserString) => {
+					let builder = self.state.len() - 2;
+					let accepted = match &mut self.state[builder] {
+						State::AttemptBuild(ref mut builder) => builder.addString(self.message[self.messageLen..].trim()),
+						_ => unreachable!(),
+					};
+					if accepted {
+						self.textInput.stop();
+						*deps.fontTexture = None;
+						self.state.pop();
+					}
+					else {
+						self.message.truncate(self.messageLen);
+						*deps.fontTexture = Some(createText(&self.message, deps.textureCreator, deps.font));
+					}
+				},
 				(Event::KeyDown {scancode: Some(Scancode::Return), ..}, State::GetDirection) => {
 					if let Some(dir) = match self.message[self.messageLen..].trim() {
 						"Up" => Some(Direction::Up),
@@ -307,6 +323,7 @@ impl EditorContext {
 			},
 			_ => {
 				deps.ctx.getMapMut().draw(&mut self.canvas, self.screenPos.top_left());
+				deps.ctx.getMapMut().drawOverlay(&mut self.canvas, self.screenPos.top_left());
 				deps.ctx.getMapMut().renderTile(self.previewRect, &self.previewTile, &mut self.canvas);
                 let q = deps.collisionTextures[self.currentCollision].query();
                 self.canvas.copy(&deps.collisionTextures[self.currentCollision], None, Some(Rect::new(self.previewRect.x() + 100, self.previewRect.y(), q.width, q.height)));
@@ -349,6 +366,13 @@ impl EditorContext {
             if (y as i64) < (self.screenRect.height() - 50) as i64 => {
                 let currentTilePosition = convertToTilePos(x + self.screenPos.x, y + self.screenPos.y);
                 deps.ctx.getMapMut().changeTile(currentTilePosition, self.currentTile.clone());
+           },
+            Event::MouseButtonDown {mouse_btn: MouseButton::Middle, x, y, ..}
+            if (y as i64) < (self.screenRect.height() - 50) as i64 => {
+                let currentTilePosition = convertToTilePos(x + self.screenPos.x, y + self.screenPos.y);
+                let replacement = if deps.ctx.getMap().getScreen(deps.ctx.getMap().getActiveScreenId()).unwrap().getOverlayTile(currentTilePosition) == Some(self.currentTileId) {None}
+                else {Some(self.currentTileId)};
+                deps.ctx.getMapMut().changeOverlayTile(currentTilePosition, replacement);
            },
             Event::KeyDown{scancode: Some(Scancode::Left), ..} => {
                 self.incTile(-1);
@@ -490,6 +514,13 @@ impl EditorContext {
 				self.state.push(State::GetEntityID);
 				*deps.fontTexture = Some(createText(&tmpMessage, deps.textureCreator, deps.font));
 			}
+			TileBuilderSignals::GetUserString(tmpMessage) => {
+				self.state.push(State::GetUserString);
+				self.textInput.start();
+				self.message = String::from(tmpMessage);
+				self.messageLen = tmpMessage.len() - 1;
+				*deps.fontTexture = Some(createText(&self.message, deps.textureCreator, deps.font));
+			},
 			TileBuilderSignals::GetUserUsize(tmpMessage) => {
 				self.state.push(State::GetUserUsize);
 				self.textInput.start();
@@ -510,6 +541,22 @@ impl EditorContext {
                 }
                 else {unreachable!()}
 			},
+			TileBuilderSignals::CompleteSign(tile, pos, pages) => {
+				self.currentTile = tile;
+				self.state.pop();
+				if let State::GetTile = self.state.last().unwrap() {
+					self.state.pop();
+					match self.state.last_mut().unwrap() {
+						State::AttemptBuildEntity(ref mut builder) => builder.addTile(self.currentTile.clone(), pos),
+						State::Idle => {
+							deps.ctx.getMapMut().changeTile(pos, self.currentTile.clone());
+							deps.ctx.getMapMut().setSign(pos, Some(pages));
+						},
+						_ => unimplemented!(),
+					};
+				}
+				else {unreachable!()}
+			},
 			TileBuilderSignals::InvalidId => (),
 		}	
 	}
@@ -673,6 +720,7 @@ pub fn createText<'a>(message: &str, textureCreator: &'a TextureCreator<WindowCo
 
 enum State {
 	GetUserUsize,
+	GetUserString,
 	GetDirection,
 	GetCoordinate,
     GetTile,
@@ -1,8 +1,8 @@
-use sdl2::render::{TextureCreator, Canvas};
-use sdl2::video::{WindowContext, Window};
+use sdl2::render::TextureCreator;
 use sdl2::rect::Rect;
 
 use super::{Skeleton, Generator, Snake, Rock, Cannon, SnakeBoss, EntityGenerator};
+use crate::Rendering::Renderer;
 use crate::{Player, Tile, Vector};
 use super::BoxCode;
 use super::Traits::IDRegistration;
@@ -53,7 +53,7 @@ impl EntityBuilder {
 			dir: None,
         }
 	}
-	pub fn build<'a>(&self, creator: &'a TextureCreator<WindowContext>) -> EntityBuilderSignals<'a> {
+	pub fn build<'a, C>(&self, creator: &'a TextureCreator<C>) -> EntityBuilderSignals<'a> {
 		match self.id {
 			0 => EntityBuilderSignals::Complete(Player::new(creator, self.position.0 as f32 * 50f32, self.position.1 as f32 * 50f32)),
 			1 => {
@@ -262,12 +262,12 @@ pub struct EntityRenderer<'a> {
 }
 
 impl<'a> EntityRenderer<'a> {
-	pub fn new(creator: &'a TextureCreator<WindowContext>) -> io::Result<Self> {
+	pub fn new<C>(creator: &'a TextureCreator<C>) -> io::Result<Self> {
 		Ok(EntityRenderer {
 			entities: Sprites::new(creator, ENTITY_SPRITES)?,
 		})
 	}
-	pub fn render(&self, canvas: &mut Canvas<Window>, id: u16, position: Rect) {
+	pub fn render(&self, canvas: &mut dyn Renderer, id: u16, position: Rect) {
 		self.entities.getSprite(id as usize).draw(canvas, position, false, false);
 	}
 }
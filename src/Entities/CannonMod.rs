@@ -1,22 +1,24 @@
 use serde::{Serialize, Deserialize};
 
 use sdl2::rect::{Rect, Point};
-use sdl2::render::{Canvas, TextureCreator};
-use sdl2::video::{Window, WindowContext};
+use sdl2::render::TextureCreator;
 
 use rand::prelude::*;
 
 use std::io;
 
-use super::Traits::{Collision, EntityTraitsWrappable, Entity, Counter, RegisterID, IDRegistration};
+use super::Traits::{Collision, EntityTraitsWrappable, Entity, Counter, RegisterID, IDRegistration, Subscriber};
 use super::{BoxCode, RefCode, RefCodeMut, TypedID};
 use super::Common::{DeathCounter, self};
+use crate::Rendering::Renderer;
 use crate::SpriteLoader::Animations;
 use crate::SpriteLoader::Sprites;
 use crate::{Vector, ID, Direction};
 use crate::EventProcessor::{CollisionMsg, Envelope, PO, Key};
 use crate::CollisionType;
 use crate::MapMod;
+use crate::Scheduling::RAISED_LAYER;
+use crate::SoundMod::Sound;
 
 const NAMES: &'static [&'static str] = &[
 	"CannonWalkDown",
@@ -40,17 +42,57 @@ pub struct InnerCannon {
 	pos: Vector,
 	variant: u8,
 	deathEvent: Option<DeathCounter>,
+	#[serde(default)]
+	cannonBalls: [Option<InnerCannonBall>; 3],
+	#[serde(default)]
+	timer: u16,
+	#[serde(default)]
+	idle: bool,
+	#[serde(default)]
+	stateTimer: Option<u16>,
+	#[serde(default)]
+	dir: Option<Direction>,
+	#[serde(default)]
+	elevated: u8,
+	#[serde(default)]
+	health: Option<i32>,
 }
 
 impl InnerCannon {
-	pub fn fromCannon(&Cannon { id, pos, variant, deathEvent, .. }: &Cannon) -> Self {
-		InnerCannon {id: id.getID(), pos, variant, deathEvent}
+	pub fn fromCannon(cannon: &Cannon) -> Self {
+		let &Cannon { id, pos, variant, deathEvent, timer, idle, stateTimer, dir, elevated, health, .. } = cannon;
+		InnerCannon {
+			id: id.getID(), pos, variant, deathEvent,
+			cannonBalls: InnerCannonBall::fromCannonBalls(&cannon.cannonsBalls),
+			timer, idle, elevated,
+			stateTimer: Some(stateTimer),
+			dir: Some(dir),
+			health: Some(health),
+		}
+	}
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct InnerCannonBall {
+	pos: Vector,
+	velocity: Vector,
+	die: bool,
+	timer: u16,
+}
+
+impl InnerCannonBall {
+	pub fn fromCannonBall(&CannonBall { pos, velocity, die, timer, .. }: &CannonBall) -> Self {
+		InnerCannonBall {pos, velocity, die, timer}
+	}
+	pub fn fromCannonBalls(balls: &[Option<CannonBall>; 3]) -> [Option<InnerCannonBall>; 3] {
+		[0, 1, 2].map(|i| balls[i].as_ref().map(Self::fromCannonBall))
 	}
 }
 
 #[derive(Debug)]
 pub struct CannonBall {
 	pos: Vector,
+	lastPos: Vector,
 	pub hitbox: Rect,
 	velocity: Vector,
 	pub renderPosition: Rect,
@@ -62,6 +104,7 @@ impl CannonBall {
 	pub fn new(pos: Vector, velocity: Vector) -> CannonBall {
 		CannonBall {
 			pos,
+			lastPos: pos,
 			hitbox: Rect::new(pos.0 as i32, pos.1 as i32, 15, 15),
 			velocity,
 			renderPosition: Rect::new(pos.0 as i32, pos.1 as i32, 15, 15),
@@ -69,13 +112,26 @@ impl CannonBall {
 			timer: 600,
 		}
 	}
+	pub fn fromInner(InnerCannonBall { pos, velocity, die, timer }: InnerCannonBall) -> CannonBall {
+		CannonBall {timer, die, ..CannonBall::new(pos, velocity)}
+	}
+	pub fn fromInners(balls: [Option<InnerCannonBall>; 3]) -> [Option<CannonBall>; 3] {
+		balls.map(|ball| ball.map(Self::fromInner))
+	}
 	pub fn update(&mut self) {
+		self.lastPos = self.pos;
 		self.pos += self.velocity * 4f32;
 		self.hitbox = Rect::new(self.pos.0 as i32, self.pos.1 as i32, 15, 15);
 		self.renderPosition = self.hitbox;
 		self.timer -= 1;
 		if self.timer == 0 {self.die = true;}
 	}
+	/// Where the ball is drawn, between its last two positions when `alpha` is given.
+	pub fn drawPosition(&self, alpha: Option<f32>) -> Rect {
+		let mut renderPosition = self.renderPosition;
+		if let Some(alpha) = alpha {renderPosition.reposition(Common::interpolate(self.lastPos, self.pos, alpha));}
+		renderPosition
+	}
 }
 
 #[derive(Debug)]
@@ -85,6 +141,7 @@ pub struct Cannon<'a> {
 	cannonballSprites: Sprites<'a>,
 	cannonsBalls: [Option<CannonBall>; 3],
 	pos: Vector,
+	lastPos: Vector,
 	hitbox: Rect,
 	renderPosition: Rect,
 	timer: u16,
@@ -105,13 +162,14 @@ pub struct CannonData {
 }
 
 impl<'a> Cannon<'a> {
-	fn newInt(creator: &'a TextureCreator<WindowContext>, pos: Vector, variant: u8, deathEvent: Option<DeathCounter>) -> io::Result<Self> {
+	fn newInt<C>(creator: &'a TextureCreator<C>, pos: Vector, variant: u8, deathEvent: Option<DeathCounter>) -> io::Result<Self> {
 		Ok(Cannon {
 			id: TypedID::new(ID::empty()),
 			animations: Animations::new("Resources/Images/Cannon.anim", NAMES, creator)?,
 			cannonballSprites: Sprites::new(creator, CANNONBALL)?,
 			cannonsBalls: [None, None, None],
 			pos,
+			lastPos: pos,
 			hitbox: Rect::new(pos.0 as i32, pos.1 as i32, 50, 50),
 			renderPosition: Rect::new(pos.0 as i32, pos.1 as i32, 50, 50),
 			variant,
@@ -125,7 +183,7 @@ impl<'a> Cannon<'a> {
 			health: if variant == 1 {50} else {20},
 		})
 	}
-	pub fn new(creator: &'a TextureCreator<WindowContext>, pos: Vector) -> io::Result<BoxCode<'a>> {
+	pub fn new<C>(creator: &'a TextureCreator<C>, pos: Vector) -> io::Result<BoxCode<'a>> {
 		Ok(BoxCode::Cannon(
 			Entity::new(
 				Self::newInt(creator, pos, 0, None)?,
@@ -133,9 +191,17 @@ impl<'a> Cannon<'a> {
 			)
 		))
 	}
-	pub fn fromInner(InnerCannon { id, pos, variant, deathEvent }: InnerCannon, creator: &'a TextureCreator<WindowContext>) -> io::Result<BoxCode<'a>> {
+	pub fn fromInner<C>(inner: InnerCannon, creator: &'a TextureCreator<C>) -> io::Result<BoxCode<'a>> {
+		let InnerCannon { id, pos, variant, deathEvent, cannonBalls, timer, idle, stateTimer, dir, elevated, health } = inner;
 		let mut tmp = Self::newInt(creator, pos, variant, deathEvent)?;
 		tmp.setID(TypedID::new(id));
+		tmp.cannonsBalls = CannonBall::fromInners(cannonBalls);
+		tmp.timer = timer;
+		tmp.idle = idle;
+		tmp.elevated = elevated;
+		if let Some(stateTimer) = stateTimer {tmp.stateTimer = stateTimer;}
+		if let Some(dir) = dir {tmp.dir = dir;}
+		if let Some(health) = health {tmp.health = health;}
 		Ok(BoxCode::Cannon(
 			Entity::new(
 				tmp,
@@ -155,6 +221,12 @@ impl<'a> Cannon<'a> {
 			Direction::Right => info.0,
 		}
 	}
+	fn drawAt(&self, canvas: &mut dyn Renderer, renderPosition: Rect, alpha: Option<f32>) {
+		self.animations.drawNextFrame(canvas, renderPosition);
+		for ball in self.cannonsBalls.iter().filter_map(|e| e.as_ref()) {
+			self.cannonballSprites.getSprite(0).draw(canvas, ball.drawPosition(alpha), false, false);
+		}
+	}
 	fn updatePositions(&mut self, po: &mut PO) {
 		let oldPos = self.hitbox;
 		self.hitbox.reposition(self.pos);
@@ -238,6 +310,7 @@ impl<'a> Collision for Cannon<'a> {
 }
 
 impl<'a> Counter for Cannon<'a> {}
+impl<'a> Subscriber for Cannon<'a> {}
 impl<'a> RegisterID for Cannon<'a> {
 	fn register(&mut self, id: IDRegistration) {
 		match id {
@@ -259,7 +332,7 @@ impl<'a> EntityTraitsWrappable<'a> for Cannon<'a> {
 		if let RefCode::Cannon(c) = code {Some(c as &Self)}
 		else {None}
 	}
-    fn drawPriority(&self) -> u8 {1}
+	fn drawLayer(&self) -> i32 {RAISED_LAYER}
 	fn getData(&self, data: &mut Self::Data, po: &PO, key: Key) -> Key {
 		data.spawnBall = None;
 		data.pos = Vector(0f32, 0f32);
@@ -309,11 +382,13 @@ impl<'a> EntityTraitsWrappable<'a> for Cannon<'a> {
 			}
 			po.addToPurgeList(self.id.getID());
 		}
+		self.lastPos = self.pos;
 		self.pos += data.pos + self.groundVelocity;
 		self.updatePositions(po);
 		if let Some(velocity) = data.spawnBall {
 			if let Some((i, _)) = self.cannonsBalls.iter().enumerate().filter(|b| b.1.is_none()).next() {
 				self.cannonsBalls[i] = Some(CannonBall::new(self.pos, velocity));
+				po.playSound(Sound::CannonFire);
 			}
 		}
 		for (i, ball) in self.cannonsBalls.iter_mut().enumerate().filter_map(|(i, e)| e.as_mut().map(|e| (i, e))) {
@@ -337,12 +412,12 @@ impl<'a> EntityTraitsWrappable<'a> for Cannon<'a> {
 		self.stateTimer -= 1;
 		if !self.idle && self.stateTimer == 0 {
 			self.idle = true;
-			self.stateTimer = (rand::thread_rng().gen::<f32>() * 110f32 + 10f32) as u16;
+			self.stateTimer = (po.getRng().gen::<f32>() * 110f32 + 10f32) as u16;
 		}
 		if self.idle && self.stateTimer == 0 {
 			self.idle = false;
-			self.stateTimer = (rand::thread_rng().gen::<f32>() * 60f32 + 60f32) as u16;
-			self.dir = match (rand::thread_rng().gen::<f32>() * 4f32) as u8{
+			self.stateTimer = (po.getRng().gen::<f32>() * 60f32 + 60f32) as u16;
+			self.dir = match (po.getRng().gen::<f32>() * 4f32) as u8{
 				0 => Direction::Up,
 				1 => Direction::Down,
 				2 => Direction::Left,
@@ -363,12 +438,13 @@ impl<'a> EntityTraitsWrappable<'a> for Cannon<'a> {
 	}
 	fn needsExecution(&self) -> bool {true}
 	fn tick(&mut self) {}
-	fn draw(&self, canvas: &mut Canvas<Window>) {
-		self.animations.drawNextFrame(canvas, self.renderPosition);
-		for ball in self.cannonsBalls.iter().filter_map(|e| e.as_ref()) {
-			self.cannonballSprites.getSprite(0).draw(canvas, ball.renderPosition, false, false);
-		}
-		println!("{:?}", self.renderPosition);
+	fn draw(&self, canvas: &mut dyn Renderer) {
+		self.drawAt(canvas, self.renderPosition, None);
+	}
+	fn drawInterpolated(&self, canvas: &mut dyn Renderer, alpha: f32) {
+		let mut renderPosition = self.renderPosition;
+		renderPosition.reposition(Common::interpolate(self.lastPos, self.pos, alpha));
+		self.drawAt(canvas, renderPosition, Some(alpha));
 	}
 }
 
@@ -7,6 +7,9 @@ use crate::Vector;
 use crate::MapMod::CollisionType;
 use std::cmp::Ordering;
 
+/// Movement larger than this between two steps is a teleport and is not interpolated.
+const MAX_INTERPOLATION_DISTANCE: f32 = 25f32;
+
 #[derive(Debug, Serialize, Deserialize, Copy, Clone)]
 pub struct DeathCounter {
     dst: ID,
@@ -23,6 +26,14 @@ impl DeathCounter {
 	}
 }
 
+/// Where something that moved from `last` to `current` during the last step is drawn, `alpha` of the way.
+/// Teleports (respawns, screen transitions) are drawn where they land.
+pub fn interpolate(last: Vector, current: Vector, alpha: f32) -> Vector {
+	let travelled = current - last;
+	if travelled.mag() > MAX_INTERPOLATION_DISTANCE {current}
+	else {last + travelled * alpha}
+}
+
 pub fn checkLineOfSight(pos: Vector, line: Vector, po: &PO) -> bool {
 	let m = line.1 / line.0;
 	let (startx, endx) = if line.0 > 0f32 {
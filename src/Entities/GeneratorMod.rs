@@ -1,15 +1,15 @@
-use sdl2::render::{TextureCreator, Canvas};
-use sdl2::video::{Window, WindowContext};
+use sdl2::render::TextureCreator;
 use sdl2::rect::Rect;
 
 use serde::{Serialize, Deserialize};
 
 use std::io;
 
+use crate::Rendering::Renderer;
 use crate::{Tile, ID, GameContext};
 use crate::EventProcessor::{Envelope, CollisionMsg, CounterMsg, PO, Key};
 use crate::Entities::{TypedID, BoxCode, RefCode, RefCodeMut, EntityBuilder};
-use crate::Entities::Traits::{Collision, RegisterID, EntityTraitsWrappable, Entity, Counter};
+use crate::Entities::Traits::{Collision, RegisterID, EntityTraitsWrappable, Entity, Counter, Subscriber};
 use crate::SpriteLoader::Sprites;
 
 const NAME: &'static[&'static str] = &["Resources/Images/Generator.png"];
@@ -63,7 +63,7 @@ pub struct EntityGenerator<'a> {
 }
 
 impl<'a> Generator<'a> {
-	pub fn newInt(creator: &'a TextureCreator<WindowContext>, pos: (i32, i32), tiles: Vec<(Tile, (u16, u16))>, cnt: u8) -> io::Result<Self> {
+	pub fn newInt<C>(creator: &'a TextureCreator<C>, pos: (i32, i32), tiles: Vec<(Tile, (u16, u16))>, cnt: u8) -> io::Result<Self> {
         Ok(Generator {
             renderRect: Rect::new(pos.0, pos.1, 50, 50),
             tiles,
@@ -72,7 +72,7 @@ impl<'a> Generator<'a> {
             cnt,
         })
     }
-	pub fn new(creator: &'a TextureCreator<WindowContext>, pos: (i32, i32), tiles: Vec<(Tile, (u16, u16))>, cnt: u8) -> io::Result<BoxCode<'a>> {
+	pub fn new<C>(creator: &'a TextureCreator<C>, pos: (i32, i32), tiles: Vec<(Tile, (u16, u16))>, cnt: u8) -> io::Result<BoxCode<'a>> {
 		Ok(BoxCode::Generator(
 			Entity::new(
                 Self::newInt(creator, pos, tiles, cnt)?,
@@ -80,10 +80,10 @@ impl<'a> Generator<'a> {
 			)
 		))
 	}
-	pub fn fromInnerInt(InnerGenerator { renderRect, tiles, cnt }: InnerGenerator, creator: &'a TextureCreator<WindowContext>) -> io::Result<Self> {
+	pub fn fromInnerInt<C>(InnerGenerator { renderRect, tiles, cnt }: InnerGenerator, creator: &'a TextureCreator<C>) -> io::Result<Self> {
         Self::newInt(creator, (renderRect.0, renderRect.1), tiles, cnt)
     }
-	pub fn fromInner(InnerGenerator { renderRect, tiles, cnt }: InnerGenerator, creator: &'a TextureCreator<WindowContext>) -> io::Result<BoxCode<'a>> {
+	pub fn fromInner<C>(InnerGenerator { renderRect, tiles, cnt }: InnerGenerator, creator: &'a TextureCreator<C>) -> io::Result<BoxCode<'a>> {
 		Ok(BoxCode::Generator(
 			Entity::new(
                 Self::newInt(creator, (renderRect.0, renderRect.1), tiles, cnt)?,
@@ -104,7 +104,7 @@ impl<'a> Generator<'a> {
 }
 
 impl<'a> EntityGenerator<'a> {
-	pub fn new(creator: &'a TextureCreator<WindowContext>, pos: (i32, i32), tiles: Vec<(Tile, (u16, u16))>, entities: Vec<(ID, bool)>, cnt: u8) -> io::Result<BoxCode<'a>> {
+	pub fn new<C>(creator: &'a TextureCreator<C>, pos: (i32, i32), tiles: Vec<(Tile, (u16, u16))>, entities: Vec<(ID, bool)>, cnt: u8) -> io::Result<BoxCode<'a>> {
 		Ok(BoxCode::EntityGenerator(
 			Entity::new(
                 EntityGenerator {
@@ -115,7 +115,7 @@ impl<'a> EntityGenerator<'a> {
 			)
 		))
 	}
-	pub fn fromInner(InnerEntityGenerator {gen, entities}: InnerEntityGenerator, creator: &'a TextureCreator<WindowContext>) -> io::Result<BoxCode<'a>> {
+	pub fn fromInner<C>(InnerEntityGenerator {gen, entities}: InnerEntityGenerator, creator: &'a TextureCreator<C>) -> io::Result<BoxCode<'a>> {
 		Ok(BoxCode::EntityGenerator(
 			Entity::new(
 				EntityGenerator {
@@ -157,6 +157,8 @@ impl<'a> Collision for EntityGenerator<'a> {
 
 impl<'a> RegisterID for Generator<'a> {}
 impl<'a> RegisterID for EntityGenerator<'a> {}
+impl<'a> Subscriber for Generator<'a> {}
+impl<'a> Subscriber for EntityGenerator<'a> {}
 
 impl<'a> Counter for Generator<'a> {
 	fn inc(&mut self, msg: Envelope<CounterMsg>, po: &PO) {
@@ -191,7 +193,7 @@ impl<'a> EntityTraitsWrappable<'a> for Generator<'a> {
 	}
 	fn needsExecution(&self) -> bool {self.editor}
 	fn tick(&mut self) {}
-	fn draw(&self, canvas: &mut Canvas<Window>) {
+	fn draw(&self, canvas: &mut dyn Renderer) {
 		if self.editor {
 			self.sprite.getSprite(0).draw(canvas, self.renderRect, false, false);
 		}
@@ -218,7 +220,7 @@ impl<'a> EntityTraitsWrappable<'a> for EntityGenerator<'a> {
 	}
 	fn needsExecution(&self) -> bool {self.gen.needsExecution()}
 	fn tick(&mut self) {}
-	fn draw(&self, canvas: &mut Canvas<Window>) {
+	fn draw(&self, canvas: &mut dyn Renderer) {
 		if self.gen.editor {
 			self.gen.sprite.getSprite(0).draw(canvas, self.gen.renderRect, false, false);
 		}
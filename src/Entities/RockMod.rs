@@ -1,16 +1,17 @@
 use serde::{Serialize, Deserialize};
 
 use sdl2::rect::Rect;
-use sdl2::render::{Canvas, TextureCreator};
-use sdl2::video::{Window, WindowContext};
+use sdl2::render::TextureCreator;
 
 use std::io;
 
-use super::Traits::{Collision, EntityTraitsWrappable, Entity, Counter, RegisterID};
+use super::Traits::{Collision, EntityTraitsWrappable, Entity, Counter, RegisterID, Subscriber};
 use super::{BoxCode, RefCode, RefCodeMut, TypedID};
+use super::Common;
+use crate::Rendering::Renderer;
 use crate::SpriteLoader::Animations;
 use crate::{Vector, ID, Direction};
-use crate::EventProcessor::{CollisionMsg, Envelope, PO, Key};
+use crate::EventProcessor::{CollisionMsg, Envelope, PO, Key, GROUND_PRIORITY};
 use crate::CollisionType;
 use crate::MapMod;
 
@@ -23,11 +24,17 @@ const NAMES: &'static[&'static str] = &[
 pub struct InnerRock {
 	id: ID,
 	path: Vec<(u16, u16)>,
+	#[serde(default)]
+	timer: u16,
+	#[serde(default)]
+	currentPath: usize,
+	#[serde(default)]
+	positions: Option<(Vector, Vector)>,
 }
 
 impl InnerRock {
-	pub fn fromRock(Rock {id, path, ..}: &Rock) -> Self {
-		InnerRock {id: id.getID(), path: path.clone()}
+	pub fn fromRock(Rock {id, path, timer, position, lastPos, currentPath, ..}: &Rock) -> Self {
+		InnerRock {id: id.getID(), path: path.clone(), timer: *timer, currentPath: *currentPath, positions: Some((*position, *lastPos))}
 	}
 }
 
@@ -46,7 +53,7 @@ pub struct Rock<'a> {
 }
 
 impl<'a> Rock<'a> {
-	pub fn newInt(creator: &'a TextureCreator<WindowContext>, path: Vec<(u16, u16)>) -> io::Result<Self> {
+	pub fn newInt<C>(creator: &'a TextureCreator<C>, path: Vec<(u16, u16)>) -> io::Result<Self> {
 		let position = path[0];
 		let position = Vector(position.0 as f32, position.1 as f32);
 		let dir = Self::determineDirection(&path, 0);
@@ -63,7 +70,7 @@ impl<'a> Rock<'a> {
 			dir,
 		})
 	}
-	pub fn new(creator: &'a TextureCreator<WindowContext>, path: Vec<(u16, u16)>) -> io::Result<BoxCode<'a>> {
+	pub fn new<C>(creator: &'a TextureCreator<C>, path: Vec<(u16, u16)>) -> io::Result<BoxCode<'a>> {
 		Ok(
 			BoxCode::Rock(
 				Entity::new(
@@ -73,9 +80,18 @@ impl<'a> Rock<'a> {
 			)
 		)
 	}
-	pub fn fromInner(InnerRock {id, path} : InnerRock, creator: &'a TextureCreator<WindowContext>) -> io::Result<BoxCode<'a>> {
+	pub fn fromInner<C>(InnerRock {id, path, timer, currentPath, positions} : InnerRock, creator: &'a TextureCreator<C>) -> io::Result<BoxCode<'a>> {
 		let mut rock = Self::newInt(creator, path)?;
 		rock.setID(TypedID::new(id));
+		rock.timer = timer;
+		rock.currentPath = currentPath;
+		rock.dir = Self::determineDirection(&rock.path, currentPath);
+		if let Some((position, lastPos)) = positions {
+			rock.position = position;
+			rock.lastPos = lastPos;
+			rock.hitbox = Rect::new(position.0 as i32, position.1 as i32, 50, 50);
+			rock.renderPosition = rock.hitbox;
+		}
 		Ok(BoxCode::Rock(
 			Entity::new(
 				rock,
@@ -123,6 +139,7 @@ impl<'a> Collision for Rock<'a> {
 
 impl<'a> Counter for Rock<'a> {}
 impl<'a> RegisterID for Rock<'a> {}
+impl<'a> Subscriber for Rock<'a> {}
 
 impl<'a> EntityTraitsWrappable<'a> for Rock<'a> {
 	type Data = ();
@@ -139,7 +156,7 @@ impl<'a> EntityTraitsWrappable<'a> for Rock<'a> {
 	}
 	fn getData(&self, _data: &mut Self::Data, po: &PO, key: Key) -> Key {
 		for entity in po.getCtx().getCollisionList(self.id.getID()) {
-			po.sendCollisionMsg(Envelope::new(CollisionMsg::Ground(self.hitbox, self.position - self.lastPos), entity, self.id.getID()));
+			po.sendCollisionMsg(Envelope::new(CollisionMsg::Ground(self.hitbox, self.position - self.lastPos), entity, self.id.getID()).withPriority(GROUND_PRIORITY));
 		}
 		key
 	}
@@ -166,8 +183,13 @@ impl<'a> EntityTraitsWrappable<'a> for Rock<'a> {
 	}
 	fn needsExecution(&self) -> bool {true}
 	fn tick(&mut self) {}
-	fn draw(&self, canvas: &mut Canvas<Window>) {
+	fn draw(&self, canvas: &mut dyn Renderer) {
 		self.animations.drawNextFrame(canvas, self.renderPosition);
 	}
+	fn drawInterpolated(&self, canvas: &mut dyn Renderer, alpha: f32) {
+		let mut renderPosition = self.renderPosition;
+		renderPosition.reposition(Common::interpolate(self.lastPos, self.position, alpha));
+		self.animations.drawNextFrame(canvas, renderPosition);
+	}
 }
 
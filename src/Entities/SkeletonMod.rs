@@ -1,14 +1,14 @@
 use serde::{Serialize, Deserialize};
 
 use sdl2::rect::Rect;
-use sdl2::render::{Canvas, TextureCreator};
-use sdl2::video::{Window, WindowContext};
+use sdl2::render::TextureCreator;
 
 use std::io;
 
-use super::Traits::{Collision, EntityTraitsWrappable, Entity, Counter, RegisterID, IDRegistration};
+use super::Traits::{Collision, EntityTraitsWrappable, Entity, Counter, RegisterID, IDRegistration, Subscriber};
 use super::{BoxCode, RefCode, RefCodeMut, TypedID};
-use super::Common::DeathCounter;
+use super::Common::{DeathCounter, self};
+use crate::Rendering::Renderer;
 use crate::SpriteLoader::Animations;
 use crate::{GameContext, Vector, ID};
 use crate::EventProcessor::{CollisionMsg, Envelope, PO, Key};
@@ -67,6 +67,7 @@ pub struct Skeleton<'a> {
 	renderPositionTop: Rect,
 	renderPositionBottom: Rect,
 	position: Vector,
+	lastPosition: Vector,
 	idle: bool,
 	hitbox: Rect,
 	iframeCounter: u32,
@@ -107,7 +108,7 @@ impl<'a> Skeleton<'a> {
 		let vec = other.position - self.position;
 		vec * 0.1
 	}
-	pub fn new(creator: &'a TextureCreator<WindowContext>, position: (f32, f32), global: bool) -> io::Result<BoxCode<'a>> {
+	pub fn new<C>(creator: &'a TextureCreator<C>, position: (f32, f32), global: bool) -> io::Result<BoxCode<'a>> {
 		let (timer, position, idle, iframeCounter, health, deathCounter) = (
 			0u32,
 			Vector(position.0, position.1),
@@ -124,14 +125,14 @@ impl<'a> Skeleton<'a> {
 		let hitbox = Rect::new(position.0.round() as i32, position.1.round() as i32, 50, 100);
 		Ok(BoxCode::Skeleton(
 			Entity::new(
-				Skeleton {id: TypedID::new(ID::empty()), animationsTop, animationsBottom, timer, renderPositionTop, renderPositionBottom, position, idle, hitbox, iframeCounter, health,deathCounter, global},
+				Skeleton {id: TypedID::new(ID::empty()), animationsTop, animationsBottom, timer, renderPositionTop, renderPositionBottom, position, lastPosition: position, idle, hitbox, iframeCounter, health,deathCounter, global},
 				SkeletonData{
 					nextPos: Vector(0f32, 0f32),
 				},
 			)
 		))
 	}
-	pub fn fromInner(inner: InnerSkeleton, creator: &'a TextureCreator<WindowContext>) -> io::Result<BoxCode<'a>> {
+	pub fn fromInner<C>(inner: InnerSkeleton, creator: &'a TextureCreator<C>) -> io::Result<BoxCode<'a>> {
 		Ok(BoxCode::Skeleton(
 			Entity::new(
 				Skeleton {
@@ -142,6 +143,7 @@ impl<'a> Skeleton<'a> {
 					renderPositionTop: Rect::from(inner.renderPositionTop),
 					renderPositionBottom: Rect::from(inner.renderPositionBottom),
 					position: inner.position,
+					lastPosition: inner.position,
 					idle: inner.idle,
 					hitbox: Rect::from(inner.hitbox),
 					iframeCounter: inner.iframeCounter,
@@ -158,6 +160,12 @@ impl<'a> Skeleton<'a> {
 	pub fn collidesStatic(&self, hitbox: Rect) -> bool {
 		self.hitbox.has_intersection(hitbox)
 	}
+	fn drawAt(&self, canvas: &mut dyn Renderer, renderPositionTop: Rect, renderPositionBottom: Rect) {
+		if self.iframeCounter / 10 % 2 != 1 {
+			self.animationsTop.drawNextFrame(canvas, renderPositionTop);
+			self.animationsBottom.drawNextFrame(canvas, renderPositionBottom);
+		}
+	}
 	fn updatePositions(&mut self, po: &mut PO) {
 		self.renderPositionTop.reposition(self.position);
 		self.renderPositionBottom.reposition(self.position + Vector(0f32, 50f32));
@@ -194,6 +202,7 @@ impl<'a> Collision for Skeleton<'a> {
 }
 
 impl<'a> Counter for Skeleton<'a> {}
+impl<'a> Subscriber for Skeleton<'a> {}
 
 impl<'a> RegisterID for Skeleton<'a> {
 	fn register(&mut self, id: IDRegistration) {
@@ -205,6 +214,7 @@ impl<'a> RegisterID for Skeleton<'a> {
 
 impl<'a> EntityTraitsWrappable<'a> for Skeleton<'a> {
 	type Data = SkeletonData;
+	fn drawDepth(&self) -> Option<i32> {Some(self.hitbox.bottom())}
 	fn setID(&mut self, id: TypedID<'a, Self>) {
 		self.id = id;
 	}
@@ -255,6 +265,7 @@ impl<'a> EntityTraitsWrappable<'a> for Skeleton<'a> {
 			return;
 		}
 
+		self.lastPosition = self.position;
 		self.position = data.nextPos;
 		self.updatePositions(po);
 
@@ -274,11 +285,15 @@ impl<'a> EntityTraitsWrappable<'a> for Skeleton<'a> {
 	}
 	fn needsExecution(&self) -> bool {true}
 	fn tick(&mut self) {}
-	fn draw(&self, canvas: &mut Canvas<Window>) {
-		if self.iframeCounter / 10 % 2 != 1 {
-			self.animationsTop.drawNextFrame(canvas, self.renderPositionTop);
-			self.animationsBottom.drawNextFrame(canvas, self.renderPositionBottom);
-		}
+	fn draw(&self, canvas: &mut dyn Renderer) {
+		self.drawAt(canvas, self.renderPositionTop, self.renderPositionBottom);
+	}
+	fn drawInterpolated(&self, canvas: &mut dyn Renderer, alpha: f32) {
+		let position = Common::interpolate(self.lastPosition, self.position, alpha);
+		let (mut top, mut bottom) = (self.renderPositionTop, self.renderPositionBottom);
+		top.reposition(position);
+		bottom.reposition(position + Vector(0f32, 50f32));
+		self.drawAt(canvas, top, bottom);
 	}
 }
 
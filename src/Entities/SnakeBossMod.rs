@@ -1,33 +1,45 @@
-use sdl2::render::{TextureCreator, Canvas};
-use sdl2::video::{Window, WindowContext};
+use sdl2::render::TextureCreator;
 use sdl2::rect::{Rect, Point};
 
 use serde::{Serialize, Deserialize};
 
 use std::io;
 
+use crate::Rendering::Renderer;
 use crate::{Tile, ID, GameContext, Vector};
-use crate::EventProcessor::{Envelope, CollisionMsg, CounterMsg, PO, Key};
+use crate::EventProcessor::{Envelope, CollisionMsg, CounterMsg, PO, Key, Topic};
 use crate::Entities::{TypedID, BoxCode, RefCode, RefCodeMut, EntityBuilder};
-use crate::Entities::Traits::{Collision, RegisterID, EntityTraitsWrappable, Entity, Counter};
+use crate::Entities::Traits::{Collision, RegisterID, EntityTraitsWrappable, Entity, Counter, Subscriber};
 use crate::SpriteLoader::Sprites;
 use crate::MapMod::CollisionType;
+use crate::SoundMod::Sound;
 
 use std::f32::consts;
 
 const NAMES: &'static [&'static str] = &["Resources/Images/SnakeBossHead.png", "Resources/Images/SnakeBossTail.png"];
 
 const SCREEN_CENTER: Vector = Vector(17.0 * 25.0, 12.0 * 25.0);
+/// Points checked along the loop when working out how far the head is from a `SnakeKill` tile
+const REMAINING_SAMPLES: usize = 120;
 
 #[derive(Serialize, Deserialize)]
 pub struct InnerSnakeBoss {
 	id: ID,
+	#[serde(default)]
+	angles: Option<(f32, f32)>,
+	#[serde(default)]
+	playerInformed: bool,
+	#[serde(default)]
+	activated: bool,
 }
 
 impl InnerSnakeBoss {
-	pub fn fromSnakeBoss(&SnakeBoss {id, ..} : &SnakeBoss) -> Self {
+	pub fn fromSnakeBoss(&SnakeBoss {id, angleStart, angleEnd, playerInformed, activated, ..} : &SnakeBoss) -> Self {
 		InnerSnakeBoss {
 			id: id.getID(),
+			angles: Some((angleStart, angleEnd)),
+			playerInformed,
+			activated,
 		}
 	}
 }
@@ -38,8 +50,12 @@ pub struct SnakeBoss<'a> {
 	sprites: Sprites<'a>,
 	angleStart: f32,
 	angleEnd: f32,
+	/// How far both ends turned during the last step
+	lastTurn: f32,
 	playerInformed: bool,
 	activated: bool,
+	/// Share of the loop the head still has to travel before it reaches a `SnakeKill` tile
+	remaining: f32,
 }
 
 #[derive(Debug, Default)]
@@ -48,17 +64,19 @@ pub struct SnakeBossData {
 }
 
 impl<'a> SnakeBoss<'a> {
-	fn newInt(creator: &'a TextureCreator<WindowContext>) -> io::Result<Self> {
+	fn newInt<C>(creator: &'a TextureCreator<C>) -> io::Result<Self> {
 		Ok(SnakeBoss {
 			id: TypedID::new(ID::empty()),
 			sprites: Sprites::new(creator, NAMES)?,
 			angleStart: consts::PI * (2.0 - 0.25),
 			angleEnd: consts::PI * (2.0 - 0.75),
+			lastTurn: 0.0,
 			activated: false,
 			playerInformed: false,
+			remaining: 1.0,
 		})
 	}
-	pub fn new(creator: &'a TextureCreator<WindowContext>) -> io::Result<BoxCode<'a>> {
+	pub fn new<C>(creator: &'a TextureCreator<C>) -> io::Result<BoxCode<'a>> {
 		Ok(BoxCode::SnakeBoss(
 			Entity::new(
 				Self::newInt(creator)?,
@@ -66,9 +84,15 @@ impl<'a> SnakeBoss<'a> {
 			)
 		))
 	}
-	pub fn fromInner(InnerSnakeBoss { id }: InnerSnakeBoss, creator: &'a TextureCreator<WindowContext>) -> io::Result<BoxCode<'a>> {
+	pub fn fromInner<C>(InnerSnakeBoss { id, angles, playerInformed, activated }: InnerSnakeBoss, creator: &'a TextureCreator<C>) -> io::Result<BoxCode<'a>> {
 		let mut tmp = Self::newInt(creator)?;
 		tmp.setID(TypedID::new(id));
+		if let Some((angleStart, angleEnd)) = angles {
+			tmp.angleStart = angleStart;
+			tmp.angleEnd = angleEnd;
+		}
+		tmp.playerInformed = playerInformed;
+		tmp.activated = activated;
 		Ok(BoxCode::SnakeBoss(
 			Entity::new(
 				tmp,
@@ -76,6 +100,26 @@ impl<'a> SnakeBoss<'a> {
 			)
 		))
 	}
+	/// Health for the HUD, `None` until the fight starts.
+	pub fn getRemaining(&self) -> Option<f32> {
+		if self.activated {Some(self.remaining)} else {None}
+	}
+	/// Walks the head's path ahead of it, checking the same tiles `update` does.
+	fn findRemaining(&self, po: &PO) -> f32 {
+		let screen = po.getCtx().getMap().getScreen(po.getCtx().getMap().getActiveScreenId()).unwrap();
+		for i in 0..REMAINING_SAMPLES {
+			let travelled = i as f32 / REMAINING_SAMPLES as f32;
+			let (x, y): (i32, i32) = ((self.getPos(self.angleStart + 0.45 - travelled * consts::PI * 2.0) + SCREEN_CENTER) / 50.0).into();
+			for x in (x-1)..=(x+1) {
+				for y in (y-1)..=(y+1) {
+					if let CollisionType::SnakeKill = screen.getTile((x as u16, y as u16)).getCollisionType() {
+						return travelled;
+					}
+				}
+			}
+		}
+		1.0
+	}
 	pub fn collidesStatic(&self, _hitbox: Rect) -> bool {false}
 	fn dropGates(&self, po: &PO) {
 		for i in 2..=14 {
@@ -95,6 +139,18 @@ impl<'a> SnakeBoss<'a> {
 		let render = Rect::from_center(<Vector as Into<(i32, i32)>>::into(center), 50, 250);
 		(render, angle)
 	}
+	fn drawAt(&self, canvas: &mut dyn Renderer, angleStart: f32, angleEnd: f32) {
+		let (mut render, mut angle) = self.calcDrawInfo(angleStart);
+		angle *= 180.0 / consts::PI;
+        //let center = render.center();
+        render.resize(100, 250);
+		self.sprites.getSprite(0).drawRot(canvas, render, angle as f64, Point::new(25, 125));
+		let (mut render, mut angle) = self.calcDrawInfo(angleEnd);
+		angle *= 180.0 / consts::PI;
+        //let center = render.center();
+        render.resize(100, 250);
+		self.sprites.getSprite(1).drawRot(canvas, render, angle as f64, Point::new(75, 125));
+	}
 	pub fn collides(&self, mut pos: Vector) -> bool {
 		pos -= SCREEN_CENTER;
 		let mut angle = (pos.1 / pos.0).atan();
@@ -135,7 +191,8 @@ impl<'a> SnakeBoss<'a> {
 		po.spawnTile(Tile::new(0, CollisionType::Transition(17)), (16, 7));
 		po.spawnTile(Tile::new(0, CollisionType::Transition(17)), (16, 6));
 		po.spawnTile(Tile::new(0, CollisionType::Transition(17)), (16, 8));
-		po.informPlayerSnakeBossDeath();
+		po.publish(Topic::BossDied, 0, self.id.getID());
+		po.playSound(Sound::BossDeath);
 		po.addToPurgeList(self.id.getID());
 		
 	}
@@ -147,6 +204,7 @@ impl<'a> Collision for SnakeBoss<'a> {
 
 impl<'a> RegisterID for SnakeBoss<'a> {}
 impl<'a> Counter for SnakeBoss<'a> {}
+impl<'a> Subscriber for SnakeBoss<'a> {}
 
 impl<'a> EntityTraitsWrappable<'a> for SnakeBoss<'a> {
 	type Data = SnakeBossData;
@@ -171,8 +229,9 @@ impl<'a> EntityTraitsWrappable<'a> for SnakeBoss<'a> {
 		key
 	}
 	fn update(&mut self, data: &Self::Data, po: &mut PO) {
+		self.lastTurn = 0.0;
 		if !self.playerInformed {
-			po.informPlayerSnakeBoss(self.id.getID());
+			po.publish(Topic::BossActivated, 0, self.id.getID());
 			self.playerInformed = true;
 		}
 		if !self.activated && data.activate {
@@ -181,6 +240,7 @@ impl<'a> EntityTraitsWrappable<'a> for SnakeBoss<'a> {
 		}
 		if !self.activated {return;}
 		
+		self.lastTurn = 0.01;
 		self.angleStart -= 0.01;
 		if self.angleStart <= 0f32 {
 			self.angleStart += consts::PI * 2.0;
@@ -208,6 +268,8 @@ impl<'a> EntityTraitsWrappable<'a> for SnakeBoss<'a> {
 			}
 		}
 		
+		self.remaining = self.findRemaining(po);
+
 		let (x, y): (i32, i32) = ((self.getPos(self.angleEnd + 0.1) + SCREEN_CENTER) / 50.0).into();
 		
 		for x in (x-1)..=(x+1) {
@@ -225,18 +287,12 @@ impl<'a> EntityTraitsWrappable<'a> for SnakeBoss<'a> {
 	}
 	fn needsExecution(&self) -> bool {true}
 	fn tick(&mut self) {}
-	fn draw(&self, canvas: &mut Canvas<Window>) {
-		let (mut render, mut angle) = self.calcDrawInfo(self.angleStart);
-		angle *= 180.0 / consts::PI;
-        //let center = render.center();
-        render.resize(100, 250);
-		self.sprites.getSprite(0).drawRot(canvas, render, angle as f64, Point::new(25, 125));
-		let (mut render, mut angle) = self.calcDrawInfo(self.angleEnd);
-		angle *= 180.0 / consts::PI;
-        //let center = render.center();
-        render.resize(100, 250);
-		self.sprites.getSprite(1).drawRot(canvas, render, angle as f64, Point::new(75, 125));
+	fn draw(&self, canvas: &mut dyn Renderer) {
+		self.drawAt(canvas, self.angleStart, self.angleEnd);
+	}
+	fn drawInterpolated(&self, canvas: &mut dyn Renderer, alpha: f32) {
+		let behind = self.lastTurn * (1.0 - alpha);
+		self.drawAt(canvas, self.angleStart + behind, self.angleEnd + behind);
 	}
 	fn setID(&mut self, id: TypedID<'a, Self>) {self.id = id;}
 }
-
@@ -1,15 +1,15 @@
 use sdl2::rect::Rect;
-use sdl2::render::{Canvas, TextureCreator};
-use sdl2::video::{Window, WindowContext};
+use sdl2::render::TextureCreator;
 
 use serde::{Serialize, Deserialize};
 
 use std::io;
 
+use crate::Rendering::Renderer;
 use crate::{Tile, ID, GameContext, Vector, Direction};
 use crate::EventProcessor::{Envelope, CollisionMsg, PO, Key};
 use crate::Entities::{TypedID, BoxCode, RefCode, RefCodeMut, EntityBuilder};
-use crate::Entities::Traits::{Collision, RegisterID, EntityTraitsWrappable, Entity, Counter};
+use crate::Entities::Traits::{Collision, RegisterID, EntityTraitsWrappable, Entity, Counter, Subscriber};
 use crate::SpriteLoader::Sprites;
 use crate::MapMod::CollisionType;
 
@@ -25,11 +25,13 @@ pub struct InnerSnake {
 	id: ID,
 	dir: Direction,
 	pos: (u16, u16),
+	#[serde(default)]
+	timer: Option<u16>,
 }
 
 impl InnerSnake {
-	pub fn fromSnake(&Snake { id, dir, pos, ..}: &Snake) -> InnerSnake {
-		InnerSnake {id: id.getID(), dir, pos}
+	pub fn fromSnake(&Snake { id, dir, pos, timer, ..}: &Snake) -> InnerSnake {
+		InnerSnake {id: id.getID(), dir, pos, timer: Some(timer)}
 	}
 }
 
@@ -50,7 +52,7 @@ pub struct SnakeData {
 }
 
 impl<'a> Snake<'a> {
-	fn newInt(creator: &'a TextureCreator<WindowContext>, pos: (u16, u16), dir: Direction) -> io::Result<Self> {
+	fn newInt<C>(creator: &'a TextureCreator<C>, pos: (u16, u16), dir: Direction) -> io::Result<Self> {
 		Ok(Snake {
 			id: TypedID::new(ID::empty()),
 			dir,
@@ -60,7 +62,7 @@ impl<'a> Snake<'a> {
 			timer: u16::MAX,
 		})
 	}
-	pub fn new(creator: &'a TextureCreator<WindowContext>, pos: (u16, u16), dir: Direction) -> io::Result<BoxCode<'a>> {
+	pub fn new<C>(creator: &'a TextureCreator<C>, pos: (u16, u16), dir: Direction) -> io::Result<BoxCode<'a>> {
 		Ok(BoxCode::Snake(
 			Entity::new(
 				Self::newInt(creator, pos, dir)?,
@@ -68,9 +70,10 @@ impl<'a> Snake<'a> {
 			)
 		))
 	}
-	pub fn fromInner(InnerSnake { id, dir, pos }: InnerSnake, creator: &'a TextureCreator<WindowContext>) -> io::Result<BoxCode<'a>> {
+	pub fn fromInner<C>(InnerSnake { id, dir, pos, timer }: InnerSnake, creator: &'a TextureCreator<C>) -> io::Result<BoxCode<'a>> {
 		let mut tmpSnake = Snake::newInt(creator, pos, dir)?;
 		tmpSnake.setID(TypedID::new(id));
+		if let Some(timer) = timer {tmpSnake.timer = timer;}
 		Ok(BoxCode::Snake(
 			Entity::new(
 				tmpSnake,
@@ -139,6 +142,7 @@ impl<'a> Collision for Snake<'a> {
 }
 impl<'a> RegisterID for Snake<'a> {}
 impl<'a> Counter for Snake<'a> {}
+impl<'a> Subscriber for Snake<'a> {}
 
 impl<'a> EntityTraitsWrappable<'a> for Snake<'a> {
 	type Data = SnakeData;
@@ -188,7 +192,7 @@ impl<'a> EntityTraitsWrappable<'a> for Snake<'a> {
 			if self.timer == 60 {self.timer = 0;}
 		}
 	}
-	fn draw(&self, canvas: &mut Canvas<Window>) {
+	fn draw(&self, canvas: &mut dyn Renderer) {
 		if self.timer == u16::MAX {
 			let idx = match self.dir {
 				Direction::Right => 0,
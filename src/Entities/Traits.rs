@@ -1,9 +1,8 @@
-use sdl2::video::Window;
-use sdl2::render::Canvas;
-
-use crate::EventProcessor::{Envelope, CollisionMsg, PO, Key, CounterMsg};
+use crate::Rendering::Renderer;
+use crate::EventProcessor::{Envelope, CollisionMsg, PO, Key, CounterMsg, TopicMsg};
 use super::{RefCode, RefCodeMut, TypedID};
 use crate::{GameContext, ID};
+use crate::Scheduling::GROUND_LAYER;
 
 use std::ops::{Deref, DerefMut};
 
@@ -19,14 +18,18 @@ pub trait RegisterID {
 	fn register(&mut self, id: IDRegistration) {}
 }
 
+pub trait Subscriber {
+	fn notify(&mut self, _msg: Envelope<TopicMsg>, _po: &PO) {}
+}
+
 pub trait Collision {
 	fn collide(&mut self, _msg: Envelope<CollisionMsg>, _po: &PO) {}
 	fn collideWith(&self, id: ID, other: ID, po: &PO, key: Key) -> (Option<Envelope<CollisionMsg>>, Key) {(None, key)}
 }
 
-pub trait EntityTraits : Collision + Counter + RegisterID {}
+pub trait EntityTraits : Collision + Counter + RegisterID + Subscriber {}
 impl<T> EntityTraits for T where
-	T: Collision + Counter + RegisterID {}
+	T: Collision + Counter + RegisterID + Subscriber {}
 
 pub trait EntityTraitsWrappable<'a> : EntityTraits where Self: Sized {
 	type Data;
@@ -36,8 +39,15 @@ pub trait EntityTraitsWrappable<'a> : EntityTraits where Self: Sized {
 	fn update(&mut self, data: &Self::Data, po: &mut PO);
 	fn needsExecution(&self) -> bool;
 	fn tick(&mut self);
-	fn draw(&self, canvas: &mut Canvas<Window>);
-    fn drawPriority(&self) -> u8 {0}
+	fn draw(&self, canvas: &mut dyn Renderer);
+	/// `alpha` is how far the renderer is between the previous and current step.
+	/// Only entities that keep their previous position need to override this.
+	fn drawInterpolated(&self, canvas: &mut dyn Renderer, _alpha: f32) {self.draw(canvas);}
+	/// Higher layers are drawn on top, see `Scheduling`.
+	fn drawLayer(&self) -> i32 {GROUND_LAYER}
+	/// Y coordinate used to sort entities within a layer, usually the bottom of the hitbox.
+	/// `None` draws the entity beneath the sorted ones.
+	fn drawDepth(&self) -> Option<i32> {None}
 	fn setID(&mut self, id: TypedID<'a, Self>);
 }
 
@@ -76,8 +86,10 @@ pub trait EntityDyn<'a> {
 	fn getInnerMut<'b>(&'b mut self) -> &'b mut (dyn EntityTraits + 'a);
 	fn needsExecution(&self) -> bool;
 	fn tick(&mut self);
-	fn draw(&self, canvas: &mut Canvas<Window>);
-    fn drawPriority(&self) -> u8;
+	fn draw(&self, canvas: &mut dyn Renderer);
+	fn drawInterpolated(&self, canvas: &mut dyn Renderer, alpha: f32);
+	fn drawLayer(&self) -> i32;
+	fn drawDepth(&self) -> Option<i32>;
 }
 
 impl<'a, T: EntityTraitsWrappable<'a> + 'a> EntityDyn<'a> for Entity<'a, T> {
@@ -99,11 +111,17 @@ impl<'a, T: EntityTraitsWrappable<'a> + 'a> EntityDyn<'a> for Entity<'a, T> {
 	fn tick(&mut self) {
 		self.entity.tick();
 	}
-	fn draw(&self, canvas: &mut Canvas<Window>) {
+	fn draw(&self, canvas: &mut dyn Renderer) {
 		self.entity.draw(canvas);
 	}
-    fn drawPriority(&self) -> u8 {
-        self.entity.drawPriority()
-    }
+	fn drawInterpolated(&self, canvas: &mut dyn Renderer, alpha: f32) {
+		self.entity.drawInterpolated(canvas, alpha);
+	}
+	fn drawLayer(&self) -> i32 {
+		self.entity.drawLayer()
+	}
+	fn drawDepth(&self) -> Option<i32> {
+		self.entity.drawDepth()
+	}
 }
 
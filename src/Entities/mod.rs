@@ -1,5 +1,4 @@
 use sdl2::render::TextureCreator;
-use sdl2::video::WindowContext;
 use sdl2::rect::Rect;
 
 use serde::{Serialize, Deserialize};
@@ -182,7 +181,7 @@ pub enum InnerCode {
 }
 
 impl InnerCode {
-	pub fn intoBoxCode<'a>(self, creator: &'a TextureCreator<WindowContext>) -> io::Result<BoxCode<'a>> {
+	pub fn intoBoxCode<'a, C>(self, creator: &'a TextureCreator<C>) -> io::Result<BoxCode<'a>> {
 		match self {
 			InnerCode::Player(e) => Player::fromInner(e, creator),
 			InnerCode::Skeleton(e) => Skeleton::fromInner(e, creator),
@@ -225,7 +224,7 @@ impl InnerHolder {
 			currentId: holder.currentId,
 		}
 	}
-	pub fn intoHolder<'a>(self, creator: &'a TextureCreator<WindowContext>) -> io::Result<Holder<'a>> {
+	pub fn intoHolder<'a, C>(self, creator: &'a TextureCreator<C>) -> io::Result<Holder<'a>> {
 		let mut entities = HashMap::default();
 		for (key, entity) in self.innerEntities.into_iter() {
 			entities.insert(key, UnsafeCell::new(entity.intoBoxCode(creator)?));
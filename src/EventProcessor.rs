@@ -4,11 +4,18 @@ use crate::{ID, CollisionType, Vector};
 use crate::Entities::Traits::{EntityTraits, Entity};
 use crate::Entities::Holder;
 use crate::GameContext;
+use crate::RngMod::GameRng;
+use crate::SoundMod::Sound;
 use crate::Scheduling::Scheduler;
-use crate::MapMod::{self, Tile};
+use crate::MapMod::{self, Tile, ScriptName, TileEvent};
 
 use sdl2::rect::Rect;
 
+use serde::{Serialize, Deserialize};
+
+/// Messages and events sent while delivering are delivered in another round. Delivery stops after this many rounds.
+const MAX_EVENT_ROUNDS: usize = 8;
+
 pub struct Key {
 	lock: (),
 }
@@ -23,8 +30,8 @@ enum Commands {
 	PlaceTile(Tile, (u16, u16)),
 	PlaceTiles(Tile, (u16, u16), (u16, u16)),
     ActivateEntity(ID, bool),
-	InformPlayerSnakeBoss(ID),
-	InformPlayerSnakeBossDeath,
+	Subscribe(Topic, ID),
+	Unsubscribe(Topic, ID),
 	Win,
 	Die,
 }
@@ -33,17 +40,59 @@ pub struct PO<'a> {
 	ctx: GameContext<'a>,
 	purgeList: UnsafeCell<Vec<ID>>,
 	commands: UnsafeCell<Vec<Commands>>,
+	events: UnsafeCell<Vec<Envelope<TopicMsg>>>,
+	mail: UnsafeCell<Vec<Mail>>,
+	sounds: UnsafeCell<Vec<Sound>>,
+	tileEvents: UnsafeCell<Vec<(ScriptName, TileEvent, (u16, u16))>>,
+	dialogue: UnsafeCell<Option<Vec<String>>>,
+	checkpoint: UnsafeCell<bool>,
+}
+
+enum Mail {
+	Collision(Envelope<CollisionMsg>),
+	Counter(Envelope<CounterMsg>),
 }
 
-struct Subscriber;
+impl Mail {
+	/// Highest priority first. Ties are broken by receiver and sender so the order
+	/// never depends on which entity happened to send first.
+	fn sortKey(&self) -> (std::cmp::Reverse<i32>, ID, ID) {
+		let (priority, recv, sender) = match self {
+			Mail::Collision(e) => (e.priority, e.recv, e.sender),
+			Mail::Counter(e) => (e.priority, e.recv, e.sender),
+		};
+		(std::cmp::Reverse(priority), recv, sender)
+	}
+}
 
 pub enum CollisionMsg {
 	Damage(i32),
 	Ground(Rect, Vector),
 }
 
+/// Being carried by moving ground is applied before any damage in the same step.
+pub const GROUND_PRIORITY: i32 = 10;
+
 pub struct CounterMsg(pub i32);
 
+/// Named events entities can subscribe to through `PO::subscribe`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Topic {
+	SwitchToggled,
+	KeyPickedUp,
+	ScreenEntered,
+	BossActivated,
+	BossDied,
+	/// Level specific events
+	Named(String),
+}
+
+#[derive(Clone, Debug)]
+pub struct TopicMsg {
+	pub topic: Topic,
+	pub value: i32,
+}
+
 pub struct Envelope<T> {
 	priority: i32,
 	letter: T,
@@ -60,7 +109,13 @@ impl<T> Envelope<T> {
 			sender,
 		}
 	}
+	/// Higher priorities are delivered first, see `PO::deliverMessages`.
+	pub fn withPriority(mut self, priority: i32) -> Envelope<T> {
+		self.priority = priority;
+		self
+	}
 	pub fn getMsg(&self) -> &T {return &self.letter;}
+	pub fn getPriority(&self) -> i32 {return self.priority;}
 	pub fn getSender(&self) -> ID {return self.sender;}
 	pub fn getReciever(&self) -> ID {return self.recv;}
 }
@@ -72,12 +127,22 @@ impl Envelope<CounterMsg> {
 	pub fn send(self, recv: &mut dyn EntityTraits, po: &PO) {recv.inc(self, po);}
 }
 
+impl Envelope<TopicMsg> {
+	pub fn send(self, recv: &mut dyn EntityTraits, po: &PO) {recv.notify(self, po);}
+}
+
 impl<'a> PO<'a> {
 	pub fn new(ctx: GameContext) -> PO {
 		PO{
 			ctx,
 			purgeList: UnsafeCell::new(vec![]),
 			commands: UnsafeCell::new(vec![]),
+			events: UnsafeCell::new(vec![]),
+			mail: UnsafeCell::new(vec![]),
+			sounds: UnsafeCell::new(vec![]),
+			tileEvents: UnsafeCell::new(vec![]),
+			dialogue: UnsafeCell::new(None),
+			checkpoint: UnsafeCell::new(false),
 		}
 	}
 	pub unsafe fn getCtxMut<'b>(&'b mut self) -> &'b mut GameContext<'a> {
@@ -87,22 +152,46 @@ impl<'a> PO<'a> {
 		&self.ctx
 	}
 	//pub fn 
+	/// Queues the message until the next `deliverMessages`. Returns whether the receiver exists.
 	pub fn sendCollisionMsg(&self, msg: Envelope<CollisionMsg>) -> bool {
-		unsafe {
-			if let Some(recv) = self.getCtx().getHolder().getMut(msg.recv.mask()) {
-				msg.send(&mut *recv, self);
-				true
-			}
-			else {false}
-		}
+		let exists = unsafe {self.getCtx().getHolder().get(msg.recv.mask()).is_some()};
+		if exists {unsafe {&mut *self.mail.get()}.push(Mail::Collision(msg));}
+		exists
 	}
+	/// Queues the message until the next `deliverMessages`. Returns whether the receiver exists.
 	pub fn sendCounterMsg(&self, msg: Envelope<CounterMsg>) -> bool {
-		unsafe {
-			if let Some(recv) = self.getCtx().getHolder().getMut(msg.recv.mask()) {
-				msg.send(&mut *recv, self);
-				true
+		let exists = unsafe {self.getCtx().getHolder().get(msg.recv.mask()).is_some()};
+		if exists {unsafe {&mut *self.mail.get()}.push(Mail::Counter(msg));}
+		exists
+	}
+	/// Hands every queued message to its receiver in priority order.
+	/// Messages sent while delivering are delivered in a following round.
+	pub unsafe fn deliverMessages(&mut self) {
+		self.deliverRounds("messages", |po| po.mail.get_mut(), |po, mut mail| {
+			mail.sort_by_key(Mail::sortKey);
+			for letter in mail {
+				match letter {
+					Mail::Collision(msg) => if let Some(recv) = po.ctx.getHolder().getMut(msg.recv.mask()) {
+						msg.send(&mut *recv, po);
+					},
+					Mail::Counter(msg) => if let Some(recv) = po.ctx.getHolder().getMut(msg.recv.mask()) {
+						msg.send(&mut *recv, po);
+					},
+				}
 			}
-			else {false}
+		});
+	}
+	/// Takes everything in `queue` and hands it to `deliver`, again for whatever was queued meanwhile,
+	/// until the queue stays empty or `MAX_EVENT_ROUNDS` have passed. What is left then is dropped.
+	unsafe fn deliverRounds<T>(&mut self, what: &str, queue: fn(&mut Self) -> &mut Vec<T>, deliver: unsafe fn(&mut Self, Vec<T>)) {
+		for _ in 0..MAX_EVENT_ROUNDS {
+			let batch = std::mem::take(queue(self));
+			if batch.is_empty() {return;}
+			deliver(self, batch);
+		}
+		let dropped = std::mem::take(queue(self)).len();
+		if dropped > 0 {
+			eprintln!("Warning: {} {} were still queued after {} rounds and were dropped", dropped, what, MAX_EVENT_ROUNDS);
 		}
 	}
 	pub fn win(&self) {
@@ -111,6 +200,13 @@ impl<'a> PO<'a> {
 	pub fn die(&self) {
 		unsafe {&mut *self.commands.get()}.push(Commands::Die);
 	}
+	/// The `GameManager` remembers the level as it is at the end of this step to respawn the player in.
+	pub fn reachCheckpoint(&self) {
+		*unsafe {&mut *self.checkpoint.get()} = true;
+	}
+	pub fn takeCheckpoint(&mut self) -> bool {
+		std::mem::take(self.checkpoint.get_mut())
+	}
 	pub fn spawnTile(&self, tile: Tile, location: (u16, u16)) {
 		unsafe {&mut *self.commands.get()}.push(Commands::PlaceTile(tile, location));
 	}
@@ -120,15 +216,55 @@ impl<'a> PO<'a> {
     pub fn activateEntity(&self, entity: ID, global: bool) {
         unsafe {&mut *self.commands.get()}.push(Commands::ActivateEntity(entity, global));
     }
-	pub fn informPlayerSnakeBoss(&self, id: ID) {
-		unsafe {&mut *self.commands.get()}.push(Commands::InformPlayerSnakeBoss(id));
+	/// `id` is notified of every `topic` published from the next step on.
+	pub fn subscribe(&self, topic: Topic, id: ID) {
+		unsafe {&mut *self.commands.get()}.push(Commands::Subscribe(topic, id));
+	}
+	pub fn unsubscribe(&self, topic: Topic, id: ID) {
+		unsafe {&mut *self.commands.get()}.push(Commands::Unsubscribe(topic, id));
+	}
+	/// Played by the `GameManager` once the step is over.
+	pub fn playSound(&self, sound: Sound) {
+		unsafe {&mut *self.sounds.get()}.push(sound);
 	}
-	pub fn informPlayerSnakeBossDeath(&self) {
-		unsafe {&mut *self.commands.get()}.push(Commands::InformPlayerSnakeBossDeath);
+	/// Sounds asked for during the current step.
+	pub fn getSounds(&mut self) -> &[Sound] {
+		self.sounds.get_mut()
+	}
+	pub fn clearSounds(&mut self) {
+		self.sounds.get_mut().clear();
+	}
+	/// Handed to the level script's handler `name` once the step is over.
+	pub fn tileEvent(&self, name: ScriptName, event: TileEvent, location: (u16, u16)) {
+		unsafe {&mut *self.tileEvents.get()}.push((name, event, location));
+	}
+	/// Script tile events of the current step.
+	pub fn getTileEvents(&mut self) -> &[(ScriptName, TileEvent, (u16, u16))] {
+		self.tileEvents.get_mut()
+	}
+	pub fn clearTileEvents(&mut self) {
+		self.tileEvents.get_mut().clear();
+	}
+	/// Opens a text box with the sign's text once the step is over. Signs without text are ignored.
+	pub fn readSign(&self, location: (u16, u16)) {
+		if let Some(pages) = self.ctx.getMap().getSign(location) {
+			*unsafe {&mut *self.dialogue.get()} = Some(pages.to_vec());
+		}
+	}
+	pub fn takeDialogue(&mut self) -> Option<Vec<String>> {
+		self.dialogue.get_mut().take()
+	}
+	/// Subscribers are notified at the end of the step.
+	pub fn publish(&self, topic: Topic, value: i32, sender: ID) {
+		unsafe {&mut *self.events.get()}.push(Envelope::new(TopicMsg {topic, value}, ID::empty(), sender));
 	}
 	pub fn updatePosition(&mut self, id: ID, hitbox: Rect, prevHitbox: Rect) {
 		self.ctx.updatePosition(id, hitbox, prevHitbox);
 	}
+	/// The game-wide generator. Entities must use this instead of `thread_rng` so runs stay reproducible.
+	pub fn getRng(&mut self) -> &mut GameRng {
+		self.ctx.getRng()
+	}
 	pub fn removeCollision(&mut self, id: ID, hitbox: Rect) {
 		self.ctx.removeCollision(id, hitbox);
 	}
@@ -141,7 +277,19 @@ impl<'a> PO<'a> {
 		}
 		self.purgeList.get_mut().clear();
 	}
+	unsafe fn deliverEvents(&mut self) {
+		self.deliverRounds("events", |po| po.events.get_mut(), |po, events| {
+			for event in events {
+				for recv in po.ctx.getSubscribers(&event.letter.topic).to_vec() {
+					if let Some(entity) = po.ctx.getHolder().getMut(recv.mask()) {
+						Envelope::new(event.letter.clone(), recv, event.sender).send(&mut *entity, po);
+					}
+				}
+			}
+		});
+	}
 	pub unsafe fn doCommands(&mut self) -> u8 {
+		self.deliverEvents();
 		for command in self.commands.get_mut().drain(..) {
 			match command {
 				Commands::PlaceTile(tile, location) => self.ctx.getMapMut().changeTile(location, tile),
@@ -154,12 +302,8 @@ impl<'a> PO<'a> {
                         self.ctx.activateEntityActiveScreen(entity);
                     }
                 }
-				Commands::InformPlayerSnakeBoss(id) => {
-					self.ctx.getPlayerMut().informSnakeBoss(id);
-				}
-				Commands::InformPlayerSnakeBossDeath => {
-					self.ctx.getPlayerMut().informSnakeBossDeath();
-				}
+				Commands::Subscribe(topic, id) => self.ctx.getEventBusMut().subscribe(topic, id),
+				Commands::Unsubscribe(topic, id) => self.ctx.getEventBusMut().unsubscribe(&topic, id),
 				Commands::Win => return 1,
 				Commands::Die => return 2,
 			}
@@ -171,8 +315,38 @@ impl<'a> PO<'a> {
 	}
 }
 
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct SubscriberList {
 	subs: Vec<ID>,
+}
 
+/// Who listens to which `Topic`. Kept as a list since `Topic::Named` cannot be a JSON map key.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct EventBus {
+	topics: Vec<(Topic, SubscriberList)>,
+}
+
+impl EventBus {
+	pub fn subscribe(&mut self, topic: Topic, id: ID) {
+		match self.topics.iter_mut().find(|(t, _)| *t == topic) {
+			Some((_, list)) if list.subs.contains(&id) => (),
+			Some((_, list)) => list.subs.push(id),
+			None => self.topics.push((topic, SubscriberList {subs: vec![id]})),
+		}
+	}
+	pub fn unsubscribe(&mut self, topic: &Topic, id: ID) {
+		if let Some((_, list)) = self.topics.iter_mut().find(|(t, _)| t == topic) {
+			list.subs.retain(|sub| *sub != id);
+		}
+	}
+	/// Drops every subscription of the entity, sub IDs included.
+	pub fn unsubscribeAll(&mut self, id: ID) {
+		for (_, list) in self.topics.iter_mut() {
+			list.subs.retain(|sub| sub.mask() != id.mask());
+		}
+	}
+	pub fn getSubscribers(&self, topic: &Topic) -> &[ID] {
+		self.topics.iter().find(|(t, _)| t == topic).map_or(&[], |(_, list)| &list.subs)
+	}
 }
 
@@ -1,16 +1,17 @@
-use sdl2::video::WindowContext;
 use sdl2::render::TextureCreator;
 use sdl2::rect::Rect;
 
 use serde::{Serialize, Deserialize};
 
-use crate::{ID, Map, Player, Vec2d};
+use crate::{ID, Map, Player, Vec2d, Direction};
 use crate::Entities::{Holder, InnerHolder, TypedID, Skeleton, BoxCode};
 use crate::Entities::Traits::{EntityDyn, EntityTraitsWrappable};
 use crate::IntHasher::UInt64Hasher;
 use crate::MapMod::InnerMap;
+use crate::RngMod::GameRng;
+use crate::EventProcessor::{EventBus, Topic};
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::io;
 
 #[derive(Serialize, Deserialize)]
@@ -19,6 +20,10 @@ pub struct InnerGameContext {
 	map: InnerMap,
 	player: ID,
 	globalEntities: HashSet<u64, UInt64Hasher>,
+	#[serde(default)]
+	rng: GameRng,
+	#[serde(default)]
+	bus: EventBus,
 }
 
 impl InnerGameContext {
@@ -28,17 +33,23 @@ impl InnerGameContext {
 			map: (&ctx.map as &InnerMap).clone(),
 			player: ctx.player.getID(),
 			globalEntities: ctx.globalEntities.clone(),
+			rng: ctx.rng,
+			bus: ctx.bus.clone(),
 		}
 	}
-	pub fn intoGameContext<'a>(self, creator: &'a TextureCreator<WindowContext>) -> io::Result<GameContext<'a>> {
-		Ok(GameContext {
+	pub fn intoGameContext<'a, C>(self, tileset: &str, creator: &'a TextureCreator<C>) -> io::Result<GameContext<'a>> {
+		let mut ctx = GameContext {
 			holder: self.holder.intoHolder(creator)?,
-			map: Map::restore(self.map, 0, "Resources/Images/Map1.anim", creator)?,
+			collision: CollisionGrid::new(&self.map),
+			map: Map::restore(self.map, 0, tileset, creator)?,
 			player: TypedID::new(self.player),
-			collision: Vec2d::new(vec![EntityHitbox::empty(); 17*12], 17),
-			collisionCandidates: vec![],
 			globalEntities: self.globalEntities,
-		})
+			rng: self.rng,
+			bus: self.bus,
+			lastTransition: None,
+		};
+		ctx.subscribePlayer();
+		Ok(ctx)
 	}
 }
 
@@ -46,45 +57,88 @@ pub struct GameContext<'a> {
 	pub holder: Holder<'a>,
 	pub map: Map<'a>,
 	pub player: TypedID<'a, Player<'a>>,
-	collision: Vec2d<EntityHitbox>,
-	collisionCandidates: Vec<(EntityHitbox, EntityHitbox)>,
+	collision: CollisionGrid,
 	globalEntities: HashSet<u64, UInt64Hasher>,
+	rng: GameRng,
+	bus: EventBus,
+	lastTransition: Option<Direction>,
 }
 
-#[derive(Copy, Clone, Debug)]
-struct EntityHitbox {
-	id: ID,
-	hitbox: Rect,
+/// Hitboxes of the entities on the active screen
+struct CollisionGrid {
+	/// Every tile lists the IDs whose hitbox touches it
+	tiles: Vec2d<Vec<ID>>,
+	hitboxes: HashMap<ID, Rect, UInt64Hasher>,
 }
 
-impl EntityHitbox {
-	fn empty() -> EntityHitbox {
-		EntityHitbox {
-			id: ID::empty(),
-			hitbox: Rect::new(0, 0, 0, 0),
+impl CollisionGrid {
+	/// An empty collision grid the size of the active screen.
+	fn new(map: &InnerMap) -> CollisionGrid {
+		let (width, height) = map.getActiveScreenDimensions();
+		CollisionGrid {
+			tiles: Vec2d::new(vec![vec![]; width as usize * height as usize], width as usize),
+			hitboxes: HashMap::default(),
+		}
+	}
+	fn update(&mut self, map: &InnerMap, id: ID, hitbox: Rect) {
+		self.remove(map, id);
+		for (x, y) in map.calculateCollisionBounds(hitbox) {
+			self.tiles.indexMut(y as usize, x as usize).push(id);
+		}
+		self.hitboxes.insert(id, hitbox);
+	}
+	fn remove(&mut self, map: &InnerMap, id: ID) {
+		if let Some(hitbox) = self.hitboxes.remove(&id) {
+			for (x, y) in map.calculateCollisionBounds(hitbox) {
+				self.tiles.indexMut(y as usize, x as usize).retain(|other| *other != id);
+			}
+		}
+	}
+	/// Removes every hitbox of the entity `id`, including those of its sub IDs.
+	fn removeEntity(&mut self, map: &InnerMap, id: ID) {
+		let hitboxes: Vec<ID> = self.hitboxes.keys().filter(|other| other.mask() == id.mask()).copied().collect();
+		for hitbox in hitboxes {
+			self.remove(map, hitbox);
+		}
+	}
+	fn getCollisionList(&self, map: &InnerMap, id: ID) -> Vec<ID> {
+		let mut list = vec![];
+		if let Some(&hitbox) = self.hitboxes.get(&id) {
+			for (x, y) in map.calculateCollisionBounds(hitbox) {
+				for &other in self.tiles.index(y as usize, x as usize) {
+					if other != id && !list.contains(&other) && self.hitboxes[&other].has_intersection(hitbox) {
+						list.push(other);
+					}
+				}
+			}
 		}
+		list
 	}
-	fn isEmpty(&self) -> bool {
-		self.id.isEmpty()
+	fn clear(&mut self) {
+		self.tiles.iter_mut().for_each(Vec::clear);
+		self.hitboxes.clear();
 	}
 }
 
 impl<'a> GameContext<'a> {
-	pub fn new(map: Map<'a>, creator: &'a TextureCreator<WindowContext>) -> GameContext<'a> {
+	pub fn new<C>(map: Map<'a>, creator: &'a TextureCreator<C>) -> GameContext<'a> {
 		let holder = Holder::new();
 		//unsafe {holder.add::<Player>(Player::new(creator, 50f32, 50f32).unwrap())};
 		//unsafe { holder.add::<Skeleton>(Skeleton::new(creator, (50f32, 50f32)).unwrap())};
 
 		let mut ctx = GameContext {
 			holder,
+			collision: CollisionGrid::new(&map),
 			map,
 			player: TypedID::new(ID::empty()), 
-			collision: Vec2d::new(vec![EntityHitbox::empty(); 17*12], 17),
-			collisionCandidates: vec![],
 			globalEntities: HashSet::default(),
+			rng: GameRng::fromEntropy(),
+			bus: EventBus::default(),
+			lastTransition: None,
 		};
 		ctx.addEntityGlobal::<Player>(Player::new(creator, 50f32, 50f32).unwrap());
 		ctx.player = TypedID::new(ctx.holder.getCurrentID());
+		ctx.subscribePlayer();
 		ctx
 	}
 	pub fn addEntityActiveScreen<T: EntityTraitsWrappable<'a> + 'a>(&mut self, entity: BoxCode<'a>) -> Option<ID> {
@@ -119,6 +173,8 @@ impl<'a> GameContext<'a> {
     }
 	pub unsafe fn removeEntity(&mut self, id: ID) -> Result<BoxCode<'a>, (Option<BoxCode<'a>>, &'static str)> {
 		let res = self.holder.remove(id);
+		self.bus.unsubscribeAll(id);
+		self.collision.removeEntity(&self.map, id);
 		if self.globalEntities.remove(&id.getID()) {
 			res.ok_or((None, "Entity does not exist and global entities are corrupted"))
 		}
@@ -130,55 +186,30 @@ impl<'a> GameContext<'a> {
 		}
 	}
 	
-	pub fn updatePosition<'b>(&'b mut self, id: ID, hitbox: Rect, prevHitbox: Rect) {
-		self.removeCollisionInternal(id, prevHitbox);
-
-		let iter = self.map.calculateCollisionBounds(hitbox);
-		let entry = EntityHitbox {id, hitbox};
-		for location in iter {
-			let tmp = self.collision.indexMut(location.1 as usize, location.0 as usize);
-			if !tmp.isEmpty() && Self::getCollisionListInternal(&self.collisionCandidates, id).find(|e| e.1.id == tmp.id).is_none() {
-				self.collisionCandidates.push((entry, *tmp));
-				let length = Self::getCollisionListInternal(&self.collisionCandidates, tmp.id).enumerate().last().map(|e| e.0 + 1).unwrap_or(0);
-				let prevLength = self.collisionCandidates.len();
-				self.collisionCandidates.resize(length * 2 + prevLength, (EntityHitbox::empty(), EntityHitbox::empty()));
-				{
-					let (candidates, empty) = self.collisionCandidates.split_at_mut(prevLength);
-					let (empty1, empty2) = empty.split_at_mut(length);
-					let mut iter = Self::getCollisionListInternal(candidates, tmp.id).map(|e| (entry, e.1));
-					let mut iter2 = Self::getCollisionListInternal(candidates, tmp.id).map(|e| (e.1, entry));
-					empty1.fill_with(|| iter.next().unwrap());
-					empty2.fill_with(|| iter2.next().unwrap());
-				}
-				//self.collisionCandidates.extend(Self::getCollisionListInternal(&self.collisionCandidates, tmp.id));
-
-				self.collisionCandidates.push((*tmp, entry));
-			}
-			*tmp = entry;
-		}
+	pub fn updatePosition<'b>(&'b mut self, id: ID, hitbox: Rect, _prevHitbox: Rect) {
+		self.collision.update(&self.map, id, hitbox);
 	}
-	fn removeCollisionInternal(&mut self, id: ID, hitbox: Rect) {
-		let iter = self.map.calculateCollisionBounds(hitbox);
-		for location in iter {
-			let tmp = self.collision.indexMut(location.1 as usize, location.0 as usize);
-			if tmp.id == id {*tmp = EntityHitbox::empty();}
-		}
+	/// Everything whose hitbox currently overlaps the hitbox registered for `id`.
+	pub fn getCollisionList<'b>(&'b self, id: ID) -> impl Iterator<Item=ID> + 'b {
+		self.collision.getCollisionList(&self.map, id).into_iter()
 	}
-	fn getCollisionListInternal<'b>(candidates: &'b [(EntityHitbox, EntityHitbox)], id: ID) -> impl Iterator<Item=(EntityHitbox, EntityHitbox)> + 'b {
-		candidates.iter().filter(move |e| e.0.id == id).map(|e| *e)
+	pub fn removeCollision(&mut self, id: ID, _hitbox: Rect) {
+		self.collision.remove(&self.map, id);
 	}
-	pub fn getCollisionList<'b>(&'b self, id: ID) -> impl Iterator<Item=ID> + 'b {
-		Self::getCollisionListInternal(&self.collisionCandidates, id).filter(|e| e.0.hitbox.has_intersection(e.1.hitbox)).map(|e| e.1.id)
+	/// Follows `hitbox` onto the next screen if it left the active one.
+	/// The collision grid is rebuilt to the new screen's dimensions.
+	pub fn transitionScreen(&mut self, hitbox: Rect) -> Option<Rect> {
+		let (hitbox, direction) = self.map.transitionScreen(hitbox)?;
+		self.collision = CollisionGrid::new(&self.map);
+		self.lastTransition = Some(direction);
+		Some(hitbox)
 	}
-	pub fn removeCollision(&mut self, id: ID, hitbox: Rect) {
-		self.removeCollisionInternal(id, hitbox);
-		self.collisionCandidates.retain(|pair| pair.0.id != id && pair.1.id != id);
+	/// Direction of the last screen transition, if there was one since the last call.
+	pub fn takeTransition(&mut self) -> Option<Direction> {
+		self.lastTransition.take()
 	}
 	pub fn disableEntityCollisionFrame(&mut self) {
-		self.collision.fill(EntityHitbox::empty());
-	}
-	pub fn resetCollisionLists<'b>(&'b mut self) {
-		self.collisionCandidates.clear();
+		self.collision.clear();
 	}
 
 	pub unsafe fn getEntityAtPositionActiveScreen(&self, hitbox: Rect) -> Option<ID> {
@@ -204,6 +235,9 @@ impl<'a> GameContext<'a> {
 	pub fn getMapMut<'b>(&'b mut self) -> &'b mut Map<'a> {
 		&mut self.map
 	}
+	pub fn getPlayer<'b>(&'b self) -> &'b Player<'a> {
+		self.holder.getTyped(self.player).unwrap()
+	}
 	pub fn getPlayerMut<'b>(&'b mut self) -> &'b mut Player<'a> {
 		self.holder.getMutTyped(self.player).unwrap()
 	}
@@ -216,6 +250,28 @@ impl<'a> GameContext<'a> {
 	pub fn getHolder<'b>(&'b self) -> &'b Holder<'a> {
 		&self.holder
 	}
+	/// Maps saved before the event bus existed have no subscriptions, so the player's are restored here.
+	fn subscribePlayer(&mut self) {
+		for topic in Player::TOPICS {
+			self.bus.subscribe(topic.clone(), self.player.getID());
+		}
+	}
+	pub fn getSubscribers(&self, topic: &Topic) -> &[ID] {
+		self.bus.getSubscribers(topic)
+	}
+	pub fn getEventBusMut(&mut self) -> &mut EventBus {
+		&mut self.bus
+	}
+	pub fn getRng(&mut self) -> &mut GameRng {
+		&mut self.rng
+	}
+	/// Restart gameplay randomness from `seed`.
+	pub fn setSeed(&mut self, seed: u64) {
+		self.rng = GameRng::new(seed);
+	}
+	pub fn getSeed(&self) -> u64 {
+		self.rng.getSeed()
+	}
 	pub unsafe fn entityIter<'b>(&'b self) -> impl Iterator<Item = (ID, &'b (dyn EntityDyn<'a> + 'a))> {
 		self.holder.iter()
 	}
@@ -229,3 +285,65 @@ impl<'a> GameContext<'a> {
 		self.map.getScreen(self.map.getActiveScreenId()).unwrap().getEntitiesIter()
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn screen() -> InnerMap {
+		let mut map = InnerMap::new();
+		map.addScreen(17, 12, (0, 0));
+		map
+	}
+
+	fn sorted(mut ids: Vec<ID>) -> Vec<ID> {
+		ids.sort();
+		ids
+	}
+
+	#[test]
+	fn findsEveryEntityInASharedTile() {
+		let map = screen();
+		let mut grid = CollisionGrid::new(&map);
+		let ids = [ID::new(1, 0), ID::new(2, 0), ID::new(3, 0), ID::new(4, 0)];
+		for (i, &id) in ids.iter().enumerate() {
+			grid.update(&map, id, Rect::new(110 + i as i32 * 5, 110, 20, 20));
+		}
+		assert_eq!(sorted(grid.getCollisionList(&map, ids[0])), vec![ids[1], ids[2], ids[3]]);
+		assert_eq!(sorted(grid.getCollisionList(&map, ids[3])), vec![ids[0], ids[1], ids[2]]);
+		// Sharing a tile is not enough, the hitboxes have to overlap
+		let apart = ID::new(5, 0);
+		grid.update(&map, apart, Rect::new(146, 146, 3, 3));
+		assert!(grid.getCollisionList(&map, apart).is_empty());
+	}
+
+	#[test]
+	fn removingLeavesTheOthersInTheTile() {
+		let map = screen();
+		let mut grid = CollisionGrid::new(&map);
+		let (a, b, c) = (ID::new(1, 0), ID::new(2, 0), ID::new(3, 0));
+		for id in [a, b, c] {
+			grid.update(&map, id, Rect::new(110, 110, 20, 20));
+		}
+		grid.remove(&map, b);
+		assert_eq!(sorted(grid.getCollisionList(&map, a)), vec![c]);
+		assert!(grid.getCollisionList(&map, b).is_empty());
+		assert!(grid.tiles.index(2, 2).iter().all(|&id| id != b));
+		// Moving away clears the old tiles too
+		grid.update(&map, c, Rect::new(600, 400, 20, 20));
+		assert!(grid.getCollisionList(&map, a).is_empty());
+		assert_eq!(grid.tiles.index(2, 2), &vec![a]);
+	}
+
+	#[test]
+	fn removingAnEntityTakesItsSubIDs() {
+		let map = screen();
+		let mut grid = CollisionGrid::new(&map);
+		let (cannon, ball, other) = (ID::new(1, 0), ID::new(1, 0).sub(1), ID::new(2, 0));
+		for id in [cannon, ball, other] {
+			grid.update(&map, id, Rect::new(110, 110, 20, 20));
+		}
+		grid.removeEntity(&map, cannon);
+		assert!(grid.getCollisionList(&map, other).is_empty());
+	}
+}
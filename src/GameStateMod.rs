@@ -0,0 +1,86 @@
+use sdl2::event::Event;
+use sdl2::keyboard::Scancode;
+
+/// What the game is showing. Only `Playing` advances the simulation.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum GameState {
+	Title,
+	Playing,
+	Paused,
+	GameOver,
+	LevelComplete,
+	Credits,
+}
+
+impl GameState {
+	/// Heading and prompt for the states that are a screen of their own.
+	pub fn getText(&self) -> (&'static str, &'static str) {
+		match self {
+			GameState::Title => ("Ninja Dungeon", "Press Return to start"),
+			GameState::Paused => ("Paused", ""),
+			GameState::GameOver => ("Game Over", "Press Return to try again"),
+			GameState::LevelComplete => ("Level Complete", "Press Return to continue"),
+			GameState::Credits => ("Thanks for playing", "Press Return to quit"),
+			GameState::Playing => ("", ""),
+		}
+	}
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PauseItem {
+	Resume,
+	RestartRoom,
+	Options,
+	Quit,
+	Interpolation,
+	Sound,
+	Controls,
+	Back,
+}
+
+const PAUSE_ITEMS: &'static [PauseItem] = &[PauseItem::Resume, PauseItem::RestartRoom, PauseItem::Options, PauseItem::Quit];
+const OPTION_ITEMS: &'static [PauseItem] = &[PauseItem::Interpolation, PauseItem::Sound, PauseItem::Controls, PauseItem::Back];
+
+/// The pause menu and its options page. Up/Down choose, Return picks, Escape goes back.
+pub struct PauseMenu {
+	items: &'static [PauseItem],
+	selected: usize,
+}
+
+impl PauseMenu {
+	pub fn new() -> PauseMenu {
+		PauseMenu {items: PAUSE_ITEMS, selected: 0}
+	}
+	/// Returns the item picked, if any. Escape picks `Resume` on the main page and `Back` on the options page.
+	pub fn handleEvent(&mut self, event: &Event) -> Option<PauseItem> {
+		match event {
+			Event::KeyDown{scancode: Some(Scancode::Up), ..} => self.selected = (self.selected + self.items.len() - 1) % self.items.len(),
+			Event::KeyDown{scancode: Some(Scancode::Down), ..} => self.selected = (self.selected + 1) % self.items.len(),
+			Event::KeyDown{scancode: Some(Scancode::Return), repeat: false, ..} => return Some(self.items[self.selected]),
+			Event::KeyDown{scancode: Some(Scancode::Escape), repeat: false, ..} => return Some(if self.items == OPTION_ITEMS {PauseItem::Back} else {PauseItem::Resume}),
+			_ => (),
+		}
+		None
+	}
+	pub fn showOptions(&mut self, show: bool) {
+		self.items = if show {OPTION_ITEMS} else {PAUSE_ITEMS};
+		self.selected = 0;
+	}
+	/// One line of text per item, plus whether it is highlighted. `interpolate` and `sound` fill in the options.
+	pub fn lines(&self, interpolate: bool, sound: bool) -> Vec<(String, bool)> {
+		let onOff = |on| if on {"On"} else {"Off"};
+		self.items.iter().enumerate().map(|(i, item)| {
+			let text = match item {
+				PauseItem::Resume => String::from("Resume"),
+				PauseItem::RestartRoom => String::from("Restart room"),
+				PauseItem::Options => String::from("Options"),
+				PauseItem::Quit => String::from("Quit"),
+				PauseItem::Interpolation => format!("Smooth motion: {}", onOff(interpolate)),
+				PauseItem::Sound => format!("Sound: {}", onOff(sound)),
+				PauseItem::Controls => String::from("Controls"),
+				PauseItem::Back => String::from("Back"),
+			};
+			(text, i == self.selected)
+		}).collect()
+	}
+}
@@ -0,0 +1,92 @@
+use sdl2::render::{Canvas, TextureCreator};
+use sdl2::surface::{Surface, SurfaceContext};
+use sdl2::pixels::{Color, PixelFormatEnum};
+use sdl2::rect::Point;
+
+use std::cell::UnsafeCell;
+
+use crate::{PO, Signals, Renderer, updateFrame, drawFrame, snapshotCtx};
+use crate::Scheduling::Scheduler;
+
+/// Drives a `GameContext` without a window or event pump. Textures are created against an
+/// offscreen software surface so maps and entities can be loaded on machines without a display.
+/// Nothing is drawn unless `render` or `renderTo` is called.
+pub struct Headless {
+	canvas: Canvas<Surface<'static>>,
+	scheduler: Scheduler,
+	frame: u64,
+	quit: bool,
+	pub advance: bool,
+	/// Taken like `GameManager`'s: on the first step, on entering a room and at checkpoints
+	roomSnapshot: Option<String>,
+}
+
+impl Headless {
+	pub fn initialize(width: u32, height: u32, color: Color) -> Result<(Headless, TextureCreator<SurfaceContext<'static>>), String> {
+		let surface = Surface::new(width, height, PixelFormatEnum::RGBA8888)?;
+		let mut canvas = surface.into_canvas()?;
+		canvas.set_draw_color(color);
+		let textureCreator = canvas.texture_creator();
+
+		Ok((Headless {canvas, scheduler: Scheduler::new(), frame: 0, quit: false, advance: false, roomSnapshot: None}, textureCreator))
+	}
+
+	/// Simulates a single frame with `signals` standing in for the keyboard.
+	/// Returns false once the level has been won or lost.
+	pub fn step<'a>(&mut self, po: &mut UnsafeCell<PO<'a>>, signals: Signals) -> bool {
+		if self.quit {return false;}
+		if self.roomSnapshot.is_none() {
+			self.roomSnapshot = snapshotCtx(po.get_mut().getCtx());
+		}
+		match unsafe {updateFrame(&self.scheduler, po, signals)} {
+			1 => {
+				self.quit = true;
+				self.advance = true;
+			},
+			2 => {
+				self.quit = true;
+				self.advance = false;
+			},
+			_ => (),
+		}
+		let entered = unsafe {po.get_mut().getCtxMut()}.takeTransition().is_some();
+		if po.get_mut().takeCheckpoint() || entered {
+			self.roomSnapshot = snapshotCtx(po.get_mut().getCtx());
+		}
+		self.frame += 1;
+		!self.quit
+	}
+
+	/// Clears the outcome of the last step so stepping can go on, e.g. with the room restored after dying.
+	pub fn resume(&mut self) {
+		self.quit = false;
+		self.advance = false;
+	}
+
+	/// See `GameManager::getRoomSnapshot`.
+	pub fn getRoomSnapshot(&self) -> Option<&str> {
+		self.roomSnapshot.as_deref()
+	}
+	pub fn resetRoomSnapshot(&mut self) {
+		self.roomSnapshot = None;
+	}
+
+	pub fn getFrame(&self) -> u64 {
+		self.frame
+	}
+
+	/// Draws the current frame onto the offscreen surface.
+	pub fn render<'a>(&mut self, po: &mut UnsafeCell<PO<'a>>) {
+		self.canvas.clear();
+		unsafe {drawFrame(&self.scheduler, po, &mut self.canvas, Point::new(0, 0), None);}
+	}
+
+	/// Draws the current frame into any other renderer, such as a `DrawRecorder`.
+	pub fn renderTo<'a>(&self, po: &mut UnsafeCell<PO<'a>>, renderer: &mut dyn Renderer) {
+		unsafe {drawFrame(&self.scheduler, po, renderer, Point::new(0, 0), None);}
+	}
+
+	pub fn saveFrame(&self, filename: &str) -> Result<(), String> {
+		self.canvas.surface().save_bmp(filename)
+	}
+}
@@ -0,0 +1,119 @@
+use sdl2::render::{Canvas, TextureCreator, BlendMode};
+use sdl2::video::Window;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+
+use std::io;
+
+use crate::GameContext;
+use crate::Entities::TypedID;
+use crate::Entities::SnakeBoss;
+use crate::Rendering::Renderer;
+use crate::SpriteLoader::Sprites;
+use crate::TextMod::TextCache;
+
+const HEALTH_FRAMES: &'static[&'static str] = &[
+	"Resources/Images/Health_full.png",
+	"Resources/Images/Health_half.png",
+	"Resources/Images/Health_empty.png",
+];
+
+enum HEALTH_IDX {
+	Full = 0,
+	Half,
+	Empty,
+}
+
+const ICON_FRAMES: &'static[&'static str] = &[
+	"Resources/Images/Key.png",
+	"Resources/Images/Sword__half.png",
+	"Resources/Images/CannonSword_up.png",
+];
+
+enum ICON_IDX {
+	Key = 0,
+	Sword,
+	CannonSword,
+}
+
+const MARGIN: i32 = 15;
+const ICON_SIZE: u32 = 25;
+const BOSS_BAR_HEIGHT: u32 = 12;
+const BOSS_BAR_COLOR: Color = Color::RGB(0xb0, 0x20, 0x20);
+const BOSS_BAR_BACKGROUND: Color = Color::RGBA(0x10, 0x10, 0x10, 0xc0);
+const TEXT_COLOR: Color = Color::WHITE;
+
+/// Everything drawn over the game that is fixed to the window: health, keys, the current weapon,
+/// the room name and, while a boss fight is on, the boss's health.
+pub struct Hud<'a> {
+	health: Sprites<'a>,
+	icons: Sprites<'a>,
+}
+
+impl<'a> Hud<'a> {
+	pub fn new<C>(creator: &'a TextureCreator<C>) -> io::Result<Hud<'a>> {
+		Ok(Hud {
+			health: Sprites::new(creator, HEALTH_FRAMES)?,
+			icons: Sprites::new(creator, ICON_FRAMES)?,
+		})
+	}
+	/// Text is skipped without a font.
+	pub fn draw(&self, ctx: &GameContext, canvas: &mut Canvas<Window>, text: Option<&mut TextCache>) {
+		let player = ctx.getPlayer();
+		let (width, _) = canvas.output_size().unwrap_or((0, 0));
+
+		let mut health = player.getHealth();
+		let mut healthRect = Rect::new(MARGIN, MARGIN, 15, 15);
+		for _ in 0..5 {
+			if health >= 10 {
+				health -= 10;
+				self.health.getSprite(HEALTH_IDX::Full as usize)
+			}
+			else if health > 0 {
+				health -= 10;
+				self.health.getSprite(HEALTH_IDX::Half as usize)
+			}
+			else {
+				self.health.getSprite(HEALTH_IDX::Empty as usize)
+			}.draw(canvas, healthRect, false, false);
+			healthRect.reposition((healthRect.x() + 15, healthRect.y()));
+		}
+
+		let row = healthRect.bottom() + 5;
+		let weapon = if player.hasCannon() {ICON_IDX::CannonSword} else {ICON_IDX::Sword};
+		self.icons.getSprite(weapon as usize).draw(canvas, Rect::new(MARGIN, row, ICON_SIZE, ICON_SIZE), false, false);
+		self.icons.getSprite(ICON_IDX::Key as usize).draw(canvas, Rect::new(MARGIN + ICON_SIZE as i32 + 10, row, ICON_SIZE, ICON_SIZE), false, false);
+
+		if let Some(text) = text {
+			let screen = ctx.getMap().getActiveScreenId();
+			let room = ctx.getMap().getScreen(screen).and_then(|screen| screen.getName()).map_or_else(|| format!("Room {}", screen), str::to_string);
+			for (line, x, right) in [(format!("x{}", player.getKeys()), MARGIN + 2 * ICON_SIZE as i32 + 12, false), (room, width as i32 - MARGIN, true)] {
+				if let Some(texture) = text.get(&line, TEXT_COLOR, None) {
+					let query = texture.query();
+					let x = if right {x - query.width as i32} else {x};
+					let y = if right {MARGIN} else {row + (ICON_SIZE as i32 - query.height as i32) / 2};
+					let _ = canvas.copy(texture, None, Rect::new(x, y, query.width, query.height));
+				}
+			}
+		}
+
+		let boss = player.getSnakeBoss()
+			.and_then(|boss| ctx.getHolder().getTyped(TypedID::<SnakeBoss>::new(boss)))
+			.and_then(|boss| boss.getRemaining());
+		if let Some(remaining) = boss {
+			let bar = Rect::new(width as i32 / 4, MARGIN, width / 2, BOSS_BAR_HEIGHT);
+			let (prevColor, prevBlend) = (canvas.draw_color(), canvas.blend_mode());
+			canvas.set_blend_mode(BlendMode::Blend);
+			canvas.set_draw_color(BOSS_BAR_BACKGROUND);
+			let _ = canvas.fill_rect(bar);
+			canvas.set_draw_color(BOSS_BAR_COLOR);
+			let filled = (bar.width() as f32 * remaining.clamp(0.0, 1.0)) as u32;
+			if filled > 0 {
+				let _ = canvas.fill_rect(Rect::new(bar.x(), bar.y(), filled, bar.height()));
+			}
+			canvas.set_draw_color(prevColor);
+			canvas.set_blend_mode(prevBlend);
+			canvas.drawRect(bar, TEXT_COLOR);
+		}
+	}
+}
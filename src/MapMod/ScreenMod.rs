@@ -1,7 +1,5 @@
 use std::collections::HashSet;
 
-use sdl2::render::Canvas;
-use sdl2::video::Window;
 use sdl2::rect::{Rect, Point};
 
 //use BinaryFileIO::BinaryDataContainer::SelfContained;
@@ -11,6 +9,7 @@ use serde::{Serialize, Deserialize};
 
 use super::{Tile, TileRenderer, InnerMap, CollisionType};
 use super::TileMod;
+use crate::Rendering::Renderer;
 use crate::{Vec2d, Direction, Vector, ID};
 use crate::IntHasher::UInt64Hasher;
 
@@ -23,6 +22,15 @@ pub struct Screen {
 	tiles: Vec2d<Tile>,
 	entities: HashSet<u64, UInt64Hasher>,
 	position: (u32, u32),
+	/// Tile ids drawn above entities, e.g. archways or treetops. Most screens have few, so only those are stored.
+	#[serde(default)]
+	overlay: Vec<((u16, u16), u16)>,
+	/// Pages of text shown by `CollisionType::Sign` tiles
+	#[serde(default)]
+	signs: Vec<((u16, u16), Vec<String>)>,
+	/// Shown in the HUD
+	#[serde(default)]
+	name: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Copy)]
@@ -87,12 +95,15 @@ impl Screen {
 			tiles: Vec2d::new(v, width as usize),
 			position,
 			entities: HashSet::default(),
+			overlay: vec![],
+			signs: vec![],
+			name: None,
 		}
 	}
 	pub fn containsPoint(&self, point: Point) -> bool {
 		Rect::new(self.position.0 as i32, self.position.1 as i32, self.width as u32, self.height as u32).contains_point(point)
 	}
-	pub fn draw(&self, tileRenderer: &mut TileRenderer, canvas: &mut Canvas<Window>, topLeft: Point) {
+	pub fn draw(&self, tileRenderer: &mut TileRenderer, canvas: &mut dyn Renderer, topLeft: Point) {
 		let mut rect = Rect::new(-topLeft.x, -topLeft.y, 50, 50);
 		for tile in self.tiles.iter() {
 			tileRenderer.draw(tile, canvas, rect);
@@ -101,6 +112,11 @@ impl Screen {
 			rect.reposition((x * (!gtEq) as i32 - topLeft.x * gtEq as i32, y + gtEq as i32 * 50));
 		}
 	}
+	pub fn drawOverlay(&self, tileRenderer: &mut TileRenderer, canvas: &mut dyn Renderer, topLeft: Point) {
+		for &((x, y), id) in self.overlay.iter() {
+			tileRenderer.draw(&Tile::preview(id), canvas, Rect::new(x as i32 * 50 - topLeft.x, y as i32 * 50 - topLeft.y, 50, 50));
+		}
+	}
 	pub fn generateIconRect(&self, scaleX: f32, scaleY: f32, topLeft: Point) -> Rect {
 		Rect::new(((self.position.0 as f32 - topLeft.x as f32) * scaleX) as i32, 
 			((self.position.1 as f32 - topLeft.y as f32) * scaleY) as i32, 
@@ -116,7 +132,7 @@ impl Screen {
 	pub fn getEntitiesIter<'a>(&'a self) -> impl Iterator<Item=ID> + 'a {
 		self.entities.iter().map(|id| ID::new(*id, 0))
 	}
-	pub fn iconDraw(&self, tileRenderer: &mut TileRenderer, canvas: &mut Canvas<Window>, location: Rect) {
+	pub fn iconDraw(&self, tileRenderer: &mut TileRenderer, canvas: &mut dyn Renderer, location: Rect) {
 		let (xIncrement, yIncrement) = (location.width() as f32 / self.width as f32, location.height() as f32 / self.height as f32);
 		let (mut posX, mut posY) = (location.x() as f32, location.y() as f32);
 		let mut rect = Rect::new(location.left(), location.top(), xIncrement as u32, yIncrement as u32);
@@ -132,6 +148,31 @@ impl Screen {
 	pub fn replaceTile(&mut self, position: (u16, u16), replacement: Tile) {
 		*self.tiles.indexMut(position.1 as usize, position.0 as usize) = replacement;
 	}
+	pub fn replaceOverlayTile(&mut self, position: (u16, u16), replacement: Option<u16>) {
+		self.overlay.retain(|&(pos, _)| pos != position);
+		if let Some(id) = replacement {
+			self.overlay.push((position, id));
+		}
+	}
+	pub fn getName(&self) -> Option<&str> {
+		self.name.as_deref()
+	}
+	pub fn setName(&mut self, name: Option<String>) {
+		self.name = name;
+	}
+	/// `None` removes the text at `position`.
+	pub fn replaceSign(&mut self, position: (u16, u16), pages: Option<Vec<String>>) {
+		self.signs.retain(|(pos, _)| *pos != position);
+		if let Some(pages) = pages {
+			self.signs.push((position, pages));
+		}
+	}
+	pub fn getSign(&self, position: (u16, u16)) -> Option<&[String]> {
+		self.signs.iter().find(|(pos, _)| *pos == position).map(|(_, pages)| pages.as_slice())
+	}
+	pub fn getOverlayTile(&self, position: (u16, u16)) -> Option<u16> {
+		self.overlay.iter().find(|&&(pos, _)| pos == position).map(|&(_, id)| id)
+	}
 	pub fn getTile(&self, position: (u16, u16)) -> &Tile {
 		self.tiles.get(position.1 as usize, position.0 as usize).unwrap_or(&TileMod::OOB)
 	}
@@ -168,13 +209,17 @@ impl Screen {
 			Direction::Right => {Point::new(0, center.y())},
 		}
 	}
+	/// Which edge `center` left the screen through.
+	pub fn exitDirection(&self, center: Point) -> Direction {
+		if center.x() < 0 {Direction::Left}
+		else if center.x() >= self.width as i32 * 50 {Direction::Right}
+		else if center.y() < 0 {Direction::Up}
+		else {Direction::Down}
+	}
     pub fn getScreen(&self, center: Point, map: &InnerMap) -> Option<(usize, Point)> {
 		let tile = self.getTile(self.pointToIndex(center));
 		if let CollisionType::Transition(screen) = tile.getCollisionType() {
-			let direction = if center.x() < 0 {Direction::Left}
-			else if center.x() >= self.width as i32 * 50 {Direction::Right}
-			else if center.y() < 0 {Direction::Up}
-			else {Direction::Down};
+			let direction = self.exitDirection(center);
 			if let Some(result) = map.getScreen(screen) {
 				Some((screen, result.getPosition(self.position, center, direction)))
 			}
@@ -192,6 +237,9 @@ impl Default for Screen {
 			tiles: Vec2d::new(Vec::new(), 0),
 			position: (0, 0),
 			entities: HashSet::default(),
+			overlay: vec![],
+			signs: vec![],
+			name: None,
 		}
 	}
 }
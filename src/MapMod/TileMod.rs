@@ -1,7 +1,8 @@
 //use BinaryFileIO::BinaryDataContainer::SelfContained;
 //use BinaryFileIO::BFStream::Extend;
 
-use serde::{Serialize, Deserialize};
+use serde::{Serialize, Deserialize, Serializer, Deserializer};
+use serde::de::Error;
 
 use sdl2::rect::Rect;
 
@@ -23,12 +24,17 @@ pub struct TileBuilder {
 	locationEnd: Option<(u16, u16)>,
 	complete: Option<Tile>,
 	entity: Option<ID>,
+	scriptName: Option<ScriptName>,
+	signText: Option<Vec<String>>,
 }
 
 pub enum TileBuilderSignals {
 	GetUserUsize(&'static str),
+	GetUserString(&'static str),
 	GetCoordinate(&'static str),
 	Complete(Tile, (u16, u16)),
+	/// A sign and the pages of text it shows
+	CompleteSign(Tile, (u16, u16), Vec<String>),
 	GetEntity(&'static str),
 	InvalidId,
 }
@@ -90,9 +96,71 @@ pub enum CollisionType {
 	SwitchToggleGateAbyss((u16, u16, u16, u16)),
 	CannonSword,
 	Win,
+	Script(ScriptName), //Calls the level script's handler of that name, see `TileEvent`
+	Sign, //Blocks the player and shows the screen's text for this location when hit with the sword
+	Checkpoint, //Stepping on it makes this the place the player respawns at after dying
 	OOB, //Represent tiles with oob coordinates
 }
 
+pub const SCRIPT_NAME_LEN: usize = 24;
+
+/// Name of a script handler. Fixed size so `CollisionType` stays `Copy`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct ScriptName([u8; SCRIPT_NAME_LEN]);
+
+impl ScriptName {
+	/// `None` if `name` is empty or longer than `SCRIPT_NAME_LEN` bytes.
+	pub fn new(name: &str) -> Option<ScriptName> {
+		if name.is_empty() || name.len() > SCRIPT_NAME_LEN {return None;}
+		let mut bytes = [0; SCRIPT_NAME_LEN];
+		bytes[..name.len()].copy_from_slice(name.as_bytes());
+		Some(ScriptName(bytes))
+	}
+	pub fn as_str(&self) -> &str {
+		let len = self.0.iter().position(|&b| b == 0).unwrap_or(SCRIPT_NAME_LEN);
+		std::str::from_utf8(&self.0[..len]).unwrap_or("")
+	}
+}
+
+impl std::fmt::Debug for ScriptName {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(f, "{:?}", self.as_str())
+	}
+}
+
+impl Serialize for ScriptName {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(self.as_str())
+	}
+}
+
+impl<'de> Deserialize<'de> for ScriptName {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let name = String::deserialize(deserializer)?;
+		ScriptName::new(&name).ok_or_else(|| D::Error::custom(format!("Invalid script name \"{}\"", name)))
+	}
+}
+
+/// What a player did to a `CollisionType::Script` tile.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TileEvent {
+	Enter,
+	Stay,
+	Exit,
+	Sword,
+}
+
+impl TileEvent {
+	pub fn getName(&self) -> &'static str {
+		match self {
+			TileEvent::Enter => "enter",
+			TileEvent::Stay => "stay",
+			TileEvent::Exit => "exit",
+			TileEvent::Sword => "sword",
+		}
+	}
+}
+
 pub const COLLISION_NAMES: &'static [&'static str] = &[
     "None",
     "Block",
@@ -113,6 +181,9 @@ pub const COLLISION_NAMES: &'static [&'static str] = &[
 	"SwitchToggleGateAbyss",
 	"CannonSword",
 	"Win",
+	"Script",
+	"Sign",
+	"Checkpoint",
     "OOB",
 ];
 
@@ -129,6 +200,8 @@ impl TileBuilder {
 			locationEnd: None,
 			complete: None,
 			entity: None,
+			scriptName: None,
+			signText: None,
 		}
 	}
 	pub fn fromTile(tile: &Tile, pos: (u16, u16)) -> TileBuilder {
@@ -141,6 +214,8 @@ impl TileBuilder {
 			locationEnd: None,
 			complete: Some(tile.clone()),
 			entity: None,
+			scriptName: None,
+			signText: None,
 		}
 
 	}
@@ -202,6 +277,23 @@ impl TileBuilder {
 			), |(x, y), (xx, yy)| CollisionType::SwitchToggleGateAbyss((x, y, xx, yy))),
 			17 => {TileBuilderSignals::Complete(Tile::new(self.id, CollisionType::CannonSword), self.pos)},
 			18 => {TileBuilderSignals::Complete(Tile::new(self.id, CollisionType::Win), self.pos)},
+			19 => {
+				if let Some(name) = self.scriptName {
+					TileBuilderSignals::Complete(Tile::new(self.id, CollisionType::Script(name)), self.pos)
+				}
+				else {
+					TileBuilderSignals::GetUserString("Enter the script handler name: ")
+				}
+			},
+			20 => {
+				if let Some(ref pages) = self.signText {
+					TileBuilderSignals::CompleteSign(Tile::new(self.id, CollisionType::Sign), self.pos, pages.clone())
+				}
+				else {
+					TileBuilderSignals::GetUserString("Enter the sign text, | starts a new page: ")
+				}
+			},
+			21 => {TileBuilderSignals::Complete(Tile::new(self.id, CollisionType::Checkpoint), self.pos)},
             _ => TileBuilderSignals::InvalidId,
         }
 	}
@@ -222,6 +314,20 @@ impl TileBuilder {
 			_ => (),
 		}
 	}
+	/// Returns false if `name` is not a valid `ScriptName`.
+	pub fn addString(&mut self, name: &str) -> bool {
+		match self.collisionType {
+			19 => {
+				self.scriptName = ScriptName::new(name);
+				self.scriptName.is_some()
+			},
+			20 if !name.is_empty() => {
+				self.signText = Some(name.split('|').map(|page| page.trim().to_string()).collect());
+				true
+			},
+			_ => false,
+		}
+	}
 	pub fn addLocation(&mut self, location: (u16, u16)) {
 		match self.collisionType {
 			5..=7 | 16 if None == self.location => self.location = Some(location),
@@ -306,3 +412,30 @@ pub fn spawnTiles(tile: Tile, location: (u16, u16), locationEnd: (u16, u16), map
 
 pub const MAX_TILE_IDX: u16 = 23;
 
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn scriptNameLength() {
+		assert!(ScriptName::new("").is_none());
+		let longest = "a".repeat(SCRIPT_NAME_LEN);
+		assert_eq!(ScriptName::new(&longest).unwrap().as_str(), longest);
+		assert!(ScriptName::new(&"a".repeat(SCRIPT_NAME_LEN + 1)).is_none());
+		assert_eq!(ScriptName::new("openDoor").unwrap().as_str(), "openDoor");
+	}
+
+	#[test]
+	fn scriptNameSerializesAsString() {
+		let name = ScriptName::new("openDoor").unwrap();
+		let json = serde_json::to_string(&CollisionType::Script(name)).unwrap();
+		assert_eq!(json, r#"{"Script":"openDoor"}"#);
+		match serde_json::from_str::<CollisionType>(&json).unwrap() {
+			CollisionType::Script(loaded) => assert_eq!(loaded, name),
+			other => panic!("Loaded {:?}", other),
+		}
+		assert!(serde_json::from_str::<ScriptName>(r#""""#).is_err());
+		assert!(serde_json::from_str::<ScriptName>(&format!("{:?}", "a".repeat(SCRIPT_NAME_LEN + 1))).is_err());
+	}
+}
@@ -1,8 +1,7 @@
 mod TileMod;
 mod ScreenMod;
 
-use sdl2::render::{TextureCreator, Canvas};
-use sdl2::video::{Window, WindowContext};
+use sdl2::render::TextureCreator;
 use sdl2::rect::{Rect, Point};
 use sdl2::pixels::Color;
 
@@ -18,9 +17,10 @@ use std::ops::{Deref, DerefMut};
 pub use TileMod::*;
 pub use ScreenMod::*;
 
+use crate::Rendering::Renderer;
 use crate::SpriteLoader::Animations;
 use crate::IntHasher::USizeHasher;
-use crate::ID;
+use crate::{ID, Direction};
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct InnerMap {
@@ -40,13 +40,13 @@ pub struct TileRenderer<'a> {
 	animations: Animations<'a>,
 }
 impl<'a> Map<'a> {
-	pub fn new(id: usize, tileset: &str, textureCreator: &'a TextureCreator<WindowContext>) -> io::Result<Map<'a>> {
+	pub fn new<C>(id: usize, tileset: &str, textureCreator: &'a TextureCreator<C>) -> io::Result<Map<'a>> {
 		Ok(Map {
 			inner: InnerMap::new(),
 			renderer: TileRenderer::new(id, tileset, textureCreator)?,
 		})
 	}
-	pub fn restore(mapData: InnerMap, id: usize, tileset: &str, textureCreator: &'a TextureCreator<WindowContext>) -> io::Result<Map<'a>> {
+	pub fn restore<C>(mapData: InnerMap, id: usize, tileset: &str, textureCreator: &'a TextureCreator<C>) -> io::Result<Map<'a>> {
 		Ok(Map {
 			inner: mapData,
 			renderer: TileRenderer::new(id, tileset, textureCreator)?,
@@ -60,21 +60,31 @@ impl<'a> Map<'a> {
 		let activeScreen = self.activeScreen;
 		self.screens.get_mut(&activeScreen).unwrap().removeEntity(id)
 	}
-	pub fn draw(&mut self, canvas: &mut Canvas<Window>, topLeft: Point) {
+	pub fn draw(&mut self, canvas: &mut dyn Renderer, topLeft: Point) {
 		self.inner.screens[&self.inner.activeScreen].draw(&mut self.renderer, canvas, topLeft);
 	}
+	/// Tiles and overlay of any screen, e.g. the one being left during a transition.
+	pub fn drawScreen(&mut self, screen: usize, canvas: &mut dyn Renderer, topLeft: Point) {
+		if let Some(screen) = self.inner.screens.get(&screen) {
+			screen.draw(&mut self.renderer, canvas, topLeft);
+			screen.drawOverlay(&mut self.renderer, canvas, topLeft);
+		}
+	}
+	/// Overlay tiles go on top of every entity below `Scheduling::OVERLAY_LAYER`.
+	pub fn drawOverlay(&mut self, canvas: &mut dyn Renderer, topLeft: Point) {
+		self.inner.screens[&self.inner.activeScreen].drawOverlay(&mut self.renderer, canvas, topLeft);
+	}
 	pub fn update(&mut self) {
 		self.renderer.update();
 	}
-	pub fn drawAll(&mut self, canvas: &mut Canvas<Window>, scale: (u32, u32), cameraRect: Rect) {
+	pub fn drawAll(&mut self, canvas: &mut dyn Renderer, scale: (u32, u32), cameraRect: Rect) {
 		let scale = (cameraRect.width() as f32 / scale.0 as f32, cameraRect.height() as f32 / scale.1 as f32);
 		for screen in self.inner.screens.values() {
 			screen.iconDraw(&mut self.renderer, canvas, screen.generateIconRect(scale.0, scale.1, cameraRect.top_left()));
 		}
-		canvas.set_draw_color(Color::RED);
-		canvas.draw_rect(self.inner.screens[&self.inner.activeScreen].generateIconRect(scale.0, scale.1, cameraRect.top_left()));
+		canvas.drawRect(self.inner.screens[&self.inner.activeScreen].generateIconRect(scale.0, scale.1, cameraRect.top_left()), Color::RED);
 	}
-	pub fn renderTile(&mut self, position: Rect, tile: &Tile, canvas: &mut Canvas<Window>) {
+	pub fn renderTile(&mut self, position: Rect, tile: &Tile, canvas: &mut dyn Renderer) {
 		self.renderer.draw(tile, canvas, position);
 	}
 }
@@ -143,6 +153,17 @@ impl InnerMap {
 	pub fn changeTile(&mut self, position: (u16, u16), replacement: Tile) {
 		self.screens.get_mut(&self.activeScreen).unwrap().replaceTile(position, replacement);
 	}
+	/// `None` removes the overlay tile at `position`.
+	pub fn changeOverlayTile(&mut self, position: (u16, u16), replacement: Option<u16>) {
+		self.screens.get_mut(&self.activeScreen).unwrap().replaceOverlayTile(position, replacement);
+	}
+	pub fn setSign(&mut self, position: (u16, u16), pages: Option<Vec<String>>) {
+		self.screens.get_mut(&self.activeScreen).unwrap().replaceSign(position, pages);
+	}
+	/// Text of the sign at `position` on the active screen.
+	pub fn getSign(&self, position: (u16, u16)) -> Option<&[String]> {
+		self.screens[&self.activeScreen].getSign(position)
+	}
 	pub fn incrementCurrentScreen(&mut self) {
 		for screen in (self.activeScreen + 1)..self.nextId {
 			if self.screens.contains_key(&screen) {
@@ -172,22 +193,31 @@ impl InnerMap {
 	pub fn getActiveScreenId(&self) -> usize {
 		self.activeScreen
 	}
+	pub fn getLastActiveScreenId(&self) -> usize {
+		self.lastActiveScreen
+	}
+	/// Width and height of the active screen in tiles.
+	pub fn getActiveScreenDimensions(&self) -> (u16, u16) {
+		self.screens[&self.activeScreen].getDimensions()
+	}
 	pub fn moveActiveScreen(&mut self, newPos: (u32, u32)) {
 		self.screens.get_mut(&self.activeScreen).unwrap().moveToPosition(newPos);
 	}
-    pub fn transitionScreen(&mut self, hitbox: Rect) -> Option<Rect> {
+	/// Also returns the direction the hitbox left the old screen in.
+    pub fn transitionScreen(&mut self, hitbox: Rect) -> Option<(Rect, Direction)> {
         let activeScreen = &self.screens[&self.activeScreen];
 		let (w, h) = activeScreen.getDimensions();
 		let screenRect = Rect::new(0, 0, w as u32 * 50, h as u32 * 50);
 		let center = hitbox.center();
 		if !screenRect.contains_point(center) {
+			let direction = activeScreen.exitDirection(center);
 			let (screen, center) = match activeScreen.getScreen(center, self) {
 				Some(data) => data,
 				None => (self.activeScreen, center),
 			};
 			self.lastActiveScreen = self.activeScreen;
 			self.activeScreen = screen;
-			Some(Rect::from_center(center, hitbox.width(), hitbox.height()))
+			Some((Rect::from_center(center, hitbox.width(), hitbox.height()), direction))
 		}
 		else {None}
     }
@@ -205,7 +235,7 @@ pub fn convertScreenCoordToTileCoord(res: (u32, u32), screenRect: Rect, point: P
 }
 
 impl<'a> TileRenderer<'a> {
-	pub fn new(id: usize, tileset: &str, creator: &'a TextureCreator<WindowContext>) -> io::Result<TileRenderer<'a>> {
+	pub fn new<C>(id: usize, tileset: &str, creator: &'a TextureCreator<C>) -> io::Result<TileRenderer<'a>> {
 		Ok(TileRenderer {
 			animations: Animations::new(tileset, TILESETS[id], creator)?,
 		})
@@ -214,7 +244,7 @@ impl<'a> TileRenderer<'a> {
 		self.animations.update();
 	}
 	// Make this better pls
-	pub fn draw(&mut self, tile: &Tile, canvas: &mut Canvas<Window>, position: Rect) {
+	pub fn draw(&mut self, tile: &Tile, canvas: &mut dyn Renderer, position: Rect) {
 		self.animations.changeAnimation(tile.getId() as usize).unwrap();
 		self.animations.drawNextFrame(canvas, position);
 	}
@@ -6,48 +6,232 @@ use std::default::Default;
 use sdl2::EventPump;
 use sdl2::event::Event;
 use sdl2::keyboard::Scancode;
+use sdl2::controller::{GameController, Button, Axis};
+
+use serde::{Serialize, Deserialize};
+use serde_json::Deserializer;
+
+use crate::Direction;
+
+use std::io::{self, ErrorKind, Error};
+use std::fs::File;
 
 //use rlua::{UserData, UserDataMethods, FromLuaMulti};
 
-pub struct SignalsBuilder {
+/// Directions held down, oldest first, so the latest press wins over keys that are still held.
+#[derive(Clone, Debug, Default)]
+pub struct HeldOrder {
+	held: Vec<Direction>,
+}
+
+impl HeldOrder {
+	/// Presses are added in the order up, down, left, right when they arrive in the same step.
+	pub fn update(&mut self, signals: Signals) {
+		for (direction, signal) in [(Direction::Up, signals.up), (Direction::Down, signals.down), (Direction::Left, signals.left), (Direction::Right, signals.right)] {
+			match signal {
+				Some(true) if !self.held.contains(&direction) => self.held.push(direction),
+				Some(false) => self.held.retain(|&held| held != direction),
+				_ => (),
+			}
+		}
+	}
+	/// The most recently pressed direction still held.
+	pub fn latest(&self) -> Option<Direction> {
+		self.held.last().copied()
+	}
+	/// -1, 0 or 1 on each axis. Of two opposite directions the later one counts.
+	pub fn axes(&self) -> (i8, i8) {
+		let mut axes = (0, 0);
+		for direction in self.held.iter() {
+			match direction {
+				Direction::Up => axes.1 = -1,
+				Direction::Down => axes.1 = 1,
+				Direction::Left => axes.0 = -1,
+				Direction::Right => axes.0 = 1,
+			}
+		}
+		axes
+	}
+}
+
+/// How far the left stick has to be pushed along an axis, out of 32767, to count as held
+const STICK_DEAD_ZONE: i32 = 8000;
+const ATTACK_BUTTON: Button = Button::A;
+
+pub struct SignalsBuilder<'m> {
 	event: bool,
-	mapping: Mapping,
+	attackPressed: bool,
+	mapping: &'m Mapping,
 }
 
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
 pub struct Signals {
 	pub up: Option<bool>,
 	pub down: Option<bool>,
 	pub left: Option<bool>,
 	pub right: Option<bool>,
 	pub attack: Option<bool>,
+	/// The attack key went down since the last step, even if it is already back up.
+	#[serde(default)]
+	pub attackPressed: bool,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Action {
+	Up,
+	Down,
+	Left,
+	Right,
+	Attack,
+}
+
+pub const ACTIONS: &'static [Action] = &[Action::Up, Action::Down, Action::Left, Action::Right, Action::Attack];
+
+impl Action {
+	pub fn getName(&self) -> &'static str {
+		match self {
+			Action::Up => "Up",
+			Action::Down => "Down",
+			Action::Left => "Left",
+			Action::Right => "Right",
+			Action::Attack => "Attack",
+		}
+	}
+}
+
+/// Keys for each action. Any one of an action's keys being held is enough.
+#[derive(Clone, Debug)]
+pub struct Mapping {
+	pub up: Vec<Scancode>,
+	pub down: Vec<Scancode>,
+	pub left: Vec<Scancode>,
+	pub right: Vec<Scancode>,
+	pub attack: Vec<Scancode>,
 }
 
-pub struct Mapping {	
-	pub up: Scancode,
-	pub down: Scancode,
-	pub left: Scancode,
-	pub right: Scancode,
-	pub attack: Scancode,
+/// How a `Mapping` is stored, with keys by their SDL names such as `"W"` or `"Left Shift"`.
+#[derive(Serialize, Deserialize)]
+struct InnerMapping {
+	up: Vec<String>,
+	down: Vec<String>,
+	left: Vec<String>,
+	right: Vec<String>,
+	attack: Vec<String>,
 }
 
-impl SignalsBuilder {
-	pub fn new(mapping: Mapping) -> SignalsBuilder {
-		SignalsBuilder{event: false, mapping}
+impl Mapping {
+	pub fn load(filename: &str) -> io::Result<Mapping> {
+		let mut deserializer = Deserializer::from_reader(File::open(filename)?);
+		let inner = InnerMapping::deserialize(&mut deserializer)?;
+		let keys = |names: Vec<String>| names.iter().map(|name| Scancode::from_name(name)
+			.ok_or_else(|| Error::new(ErrorKind::InvalidData, format!("Unknown key \"{}\"", name)))).collect::<io::Result<Vec<_>>>();
+		Ok(Mapping {
+			up: keys(inner.up)?,
+			down: keys(inner.down)?,
+			left: keys(inner.left)?,
+			right: keys(inner.right)?,
+			attack: keys(inner.attack)?,
+		})
+	}
+	pub fn save(&self, filename: &str) -> io::Result<()> {
+		let names = |keys: &[Scancode]| keys.iter().map(|key| key.name().to_string()).collect();
+		let inner = InnerMapping {
+			up: names(&self.up),
+			down: names(&self.down),
+			left: names(&self.left),
+			right: names(&self.right),
+			attack: names(&self.attack),
+		};
+		serde_json::to_writer_pretty(File::create(filename)?, &inner)?;
+		Ok(())
+	}
+	pub fn getKeys(&self, action: Action) -> &[Scancode] {
+		match action {
+			Action::Up => &self.up,
+			Action::Down => &self.down,
+			Action::Left => &self.left,
+			Action::Right => &self.right,
+			Action::Attack => &self.attack,
+		}
+	}
+	pub fn getKeysMut(&mut self, action: Action) -> &mut Vec<Scancode> {
+		match action {
+			Action::Up => &mut self.up,
+			Action::Down => &mut self.down,
+			Action::Left => &mut self.left,
+			Action::Right => &mut self.right,
+			Action::Attack => &mut self.attack,
+		}
+	}
+	/// Binds `key` to `action` alone, taking it away from any other action.
+	pub fn bind(&mut self, action: Action, key: Scancode) {
+		for &other in ACTIONS {
+			self.getKeysMut(other).retain(|&bound| bound != key);
+		}
+		self.getKeysMut(action).push(key);
+	}
+}
+
+impl Signals {
+	/// Combines two sets of signals, preferring any field set in `newer`. A press in either is kept.
+	pub fn merge(self, newer: Signals) -> Signals {
+		Signals {
+			up: newer.up.or(self.up),
+			down: newer.down.or(self.down),
+			left: newer.left.or(self.left),
+			right: newer.right.or(self.right),
+			attack: newer.attack.or(self.attack),
+			attackPressed: newer.attackPressed || self.attackPressed,
+		}
+	}
+	/// Only the actions let go, for steps that ignore presses. Dropping releases as well would
+	/// leave the player walking with nothing held.
+	pub fn releases(self) -> Signals {
+		let released = |signal: Option<bool>| signal.filter(|&held| !held);
+		Signals {
+			up: released(self.up),
+			down: released(self.down),
+			left: released(self.left),
+			right: released(self.right),
+			attack: released(self.attack),
+			attackPressed: false,
+		}
+	}
+	pub fn isEmpty(&self) -> bool {
+		self.up.is_none() && self.down.is_none() && self.left.is_none() && self.right.is_none() && self.attack.is_none() && !self.attackPressed
+	}
+}
+
+impl<'m> SignalsBuilder<'m> {
+	pub fn new(mapping: &'m Mapping) -> SignalsBuilder<'m> {
+		SignalsBuilder{event: false, attackPressed: false, mapping}
 	}
 	pub fn addEvent(&mut self, event: &Event) {
-		if let Event::KeyDown{..} | Event::KeyUp{..} = event {
+		if let Event::KeyDown{..} | Event::KeyUp{..}
+			| Event::ControllerButtonDown{..} | Event::ControllerButtonUp{..} | Event::ControllerAxisMotion{..}
+			| Event::ControllerDeviceRemoved{..} = event {
 			self.event = true;
 		}
+		match event {
+			Event::KeyDown{scancode: Some(key), repeat: false, ..} if self.mapping.attack.contains(key) => self.attackPressed = true,
+			Event::ControllerButtonDown{button: ATTACK_BUTTON, ..} => self.attackPressed = true,
+			_ => (),
+		}
 	}
-	pub fn build(self, events: &EventPump) -> Signals {
+	/// Reads the keyboard and every controller in `controllers`, any of which can hold an action.
+	pub fn build(self, events: &EventPump, controllers: &[GameController]) -> Signals {
 		if self.event {
 			let state = events.keyboard_state();
+			let pressed = |keys: &[Scancode]| keys.iter().any(|&key| state.is_scancode_pressed(key));
+			let held = |button: Button, axis: Axis, sign: i32| controllers.iter()
+				.any(|controller| controller.button(button) || controller.axis(axis) as i32 * sign > STICK_DEAD_ZONE);
 			Signals {
-				up: Some(state.is_scancode_pressed(self.mapping.up)),
-				down: Some(state.is_scancode_pressed(self.mapping.down)),
-				left: Some(state.is_scancode_pressed(self.mapping.left)),
-				right: Some(state.is_scancode_pressed(self.mapping.right)),
-				attack: Some(state.is_scancode_pressed(self.mapping.attack)),
+				up: Some(pressed(&self.mapping.up) || held(Button::DPadUp, Axis::LeftY, -1)),
+				down: Some(pressed(&self.mapping.down) || held(Button::DPadDown, Axis::LeftY, 1)),
+				left: Some(pressed(&self.mapping.left) || held(Button::DPadLeft, Axis::LeftX, -1)),
+				right: Some(pressed(&self.mapping.right) || held(Button::DPadRight, Axis::LeftX, 1)),
+				attack: Some(pressed(&self.mapping.attack) || controllers.iter().any(|controller| controller.button(ATTACK_BUTTON))),
+				attackPressed: self.attackPressed,
 			}
 		}
 		else {
@@ -57,6 +241,7 @@ impl SignalsBuilder {
 				left: None,
 				right: None,
 				attack: None,	
+				attackPressed: false,
 			}
 		}
 	}
@@ -83,21 +268,96 @@ impl UserData for Signals {
 	}
 }*/
 
-impl Default for SignalsBuilder {
-	fn default() -> Self {
-		Self::new(Mapping::default())
-	}
-}
-
 impl Default for Mapping {
 	fn default() -> Self {
 		Mapping {
-			up: Scancode::Up,
-			down: Scancode::Down,
-			left: Scancode::Left,
-			right: Scancode::Right,
-			attack: Scancode::Space,
+			up: vec![Scancode::Up],
+			down: vec![Scancode::Down],
+			left: vec![Scancode::Left],
+			right: vec![Scancode::Right],
+			attack: vec![Scancode::Space],
 		}
 	}
 }
 
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn signals(up: Option<bool>, down: Option<bool>, left: Option<bool>, right: Option<bool>) -> Signals {
+		Signals {up, down, left, right, ..Signals::default()}
+	}
+
+	#[test]
+	fn latestPressWins() {
+		let mut held = HeldOrder::default();
+		held.update(signals(None, None, Some(true), None));
+		held.update(signals(Some(true), None, None, None));
+		assert_eq!(held.latest(), Some(Direction::Up));
+		assert_eq!(held.axes(), (-1, -1));
+		held.update(signals(Some(false), None, None, None));
+		assert_eq!(held.latest(), Some(Direction::Left));
+		assert_eq!(held.axes(), (-1, 0));
+		held.update(signals(None, None, Some(false), None));
+		assert_eq!(held.latest(), None);
+		assert_eq!(held.axes(), (0, 0));
+	}
+
+	#[test]
+	fn laterOppositeDirectionCounts() {
+		let mut held = HeldOrder::default();
+		held.update(signals(None, None, None, Some(true)));
+		held.update(signals(None, None, Some(true), Some(true)));
+		assert_eq!(held.axes(), (-1, 0));
+		held.update(signals(None, None, Some(false), Some(true)));
+		assert_eq!(held.axes(), (1, 0));
+		assert_eq!(held.latest(), Some(Direction::Right));
+	}
+
+	#[test]
+	fn simultaneousPressesGoInFixedOrder() {
+		let mut held = HeldOrder::default();
+		held.update(signals(Some(true), Some(true), Some(true), Some(true)));
+		assert_eq!(held.latest(), Some(Direction::Right));
+		assert_eq!(held.axes(), (1, 1));
+		// Presses of keys already held keep their place
+		held.update(signals(Some(true), None, None, None));
+		assert_eq!(held.latest(), Some(Direction::Right));
+	}
+
+	#[test]
+	fn mappingSurvivesSaveAndLoad() {
+		let mut mapping = Mapping::default();
+		mapping.bind(Action::Up, Scancode::W);
+		mapping.bind(Action::Attack, Scancode::LShift);
+		mapping.getKeysMut(Action::Left).clear();
+		let filename = std::env::temp_dir().join(format!("NinjaDungeonKeys{}.json", std::process::id()));
+		let filename = filename.to_str().unwrap();
+		mapping.save(filename).unwrap();
+		let loaded = Mapping::load(filename);
+		let _ = std::fs::remove_file(filename);
+		let loaded = loaded.unwrap();
+		for &action in ACTIONS {
+			assert_eq!(loaded.getKeys(action), mapping.getKeys(action), "{:?}", action);
+		}
+	}
+
+	#[test]
+	fn unknownKeysAreAnError() {
+		let filename = std::env::temp_dir().join(format!("NinjaDungeonBadKeys{}.json", std::process::id()));
+		let filename = filename.to_str().unwrap();
+		std::fs::write(filename, r#"{"up": ["Nope"], "down": [], "left": [], "right": [], "attack": []}"#).unwrap();
+		let loaded = Mapping::load(filename);
+		let _ = std::fs::remove_file(filename);
+		assert_eq!(loaded.unwrap_err().kind(), ErrorKind::InvalidData);
+	}
+
+	#[test]
+	fn bindingTakesTheKeyFromOtherActions() {
+		let mut mapping = Mapping::default();
+		mapping.bind(Action::Attack, Scancode::Up);
+		assert!(mapping.getKeys(Action::Up).is_empty());
+		assert_eq!(mapping.getKeys(Action::Attack), &[Scancode::Space, Scancode::Up]);
+	}
+}
@@ -1,7 +1,6 @@
 extern crate sdl2;
 
-use sdl2::render::{TextureCreator, Canvas};
-use sdl2::video::{Window, WindowContext};
+use sdl2::render::TextureCreator;
 use sdl2::rect::{Rect, Point};
 
 use serde::{Serialize, Deserialize};
@@ -10,15 +9,18 @@ use std::io;
 
 mod SignalsMod;
 
-pub use SignalsMod::{SignalsBuilder, Signals, Mapping};
+pub use SignalsMod::{SignalsBuilder, Signals, Mapping, Action, ACTIONS, HeldOrder};
 
+use crate::Rendering::Renderer;
 use crate::SpriteLoader::{Animations, Sprites};
 use crate::{Direction, Map, CollisionType, Vector, GameContext, ID};
-use crate::Entities::Traits::{Collision, EntityTraitsWrappable, Entity, Counter, RegisterID};
+use crate::Entities::Traits::{Collision, EntityTraitsWrappable, Entity, Counter, RegisterID, Subscriber};
 use crate::Entities::{BoxCode, RefCode, RefCodeMut, TypedID, Rock, SnakeBoss};
-use crate::Entities::CannonMod::{CannonBall, CANNONBALL};
-use crate::EventProcessor::{CollisionMsg, CounterMsg, Envelope, PO, Key};
-use crate::MapMod::{self, Tile};
+use crate::Entities::Common;
+use crate::Entities::CannonMod::{CannonBall, InnerCannonBall, CANNONBALL};
+use crate::EventProcessor::{CollisionMsg, CounterMsg, Envelope, PO, Key, Topic, TopicMsg};
+use crate::MapMod::{self, Tile, ScriptName, TileEvent};
+use crate::SoundMod::Sound;
 
 const SWORD_FRAMES: &'static[&'static str] = &[
 	"Resources/Images/Sword__half.png",
@@ -28,17 +30,11 @@ const SWORD_FRAMES: &'static[&'static str] = &[
     "Resources/Images/CannonSword_down.png",
 ];
 
-const HEALTH_FRAMES: &'static[&'static str] = &[
-	"Resources/Images/Health_full.png",
-	"Resources/Images/Health_half.png",
-	"Resources/Images/Health_empty.png",
-];
+/// Pixels moved per simulation step
+const SPEED: f32 = 3.0;
 
-enum HEALTH_IDX {
-	Full = 0,
-	Half,
-	Empty,
-}
+/// Length of the death animation in simulation steps
+const DEATH_STEPS: u16 = 60;
 
 const SWORD_DOWN: (i32, i32, u32, u32) = (10, 43, 30, 30);
 const SWORD_RIGHT: (i32, i32, u32, u32) = (30, 5, 30, 30);
@@ -50,6 +46,11 @@ const SWORD_RIGHT_COLLISION: (i32, i32, u32, u32) = (49, 5, 11, 16);
 const SWORD_LEFT_COLLISION: (i32, i32, u32, u32) = (-10, 5, 11, 16);
 const SWORD_UP_COLLISION: (i32, i32, u32, u32) = (27, -10, 6, 27);
 
+impl<'a> Player<'a> {
+	/// Topics the player is always subscribed to.
+	pub const TOPICS: &'static [Topic] = &[Topic::BossActivated, Topic::BossDied];
+}
+
 const NAMES: &'static[&'static str] = &[
 	"Ninja float",
 	"Ninja right float",
@@ -99,12 +100,39 @@ pub struct InnerPlayer {
 	attacking: bool,
 	health: i32,
 	iframes: u32,
+	#[serde(default)]
+	keys: u8,
+	#[serde(default)]
+	abyss: u16,
+	#[serde(default)]
+	burn: u16,
+	#[serde(default)]
+	respawn: Vector,
+	#[serde(default)]
+	elevated: u8,
+	#[serde(default)]
+	snakeBoss: Option<ID>,
+	#[serde(default)]
+	cannon: bool,
+	#[serde(default)]
+	cannonBalls: [Option<InnerCannonBall>; 3],
+	#[serde(default)]
+	scriptTiles: Vec<((u16, u16), ScriptName)>,
+	#[serde(default)]
+	onCheckpoint: bool,
+	#[serde(default)]
+	dying: u16,
 }
 
 impl InnerPlayer {
 	pub fn fromPlayer(player: &Player) -> InnerPlayer {
-		let &Player {id, direction, timer, idle, velocity, position, hitbox, renderPosition, attackTimer, attacking, health, iframes, ..} = player;
-		InnerPlayer {id:id.getID(), direction, timer, idle, velocity, position, hitbox: hitbox.into(), renderPosition: renderPosition.into(), attackTimer, attacking, health, iframes}
+		let &Player {id, direction, timer, idle, velocity, position, hitbox, renderPosition, attackTimer, attacking, health, iframes, keys, abyss, burn, respawn, elevated, snakeBoss, cannon, onCheckpoint, dying, ..} = player;
+		InnerPlayer {
+			id:id.getID(), direction, timer, idle, velocity, position, hitbox: hitbox.into(), renderPosition: renderPosition.into(), attackTimer, attacking, health, iframes,
+			keys, abyss, burn, respawn, elevated, snakeBoss, cannon, onCheckpoint, dying,
+			cannonBalls: InnerCannonBall::fromCannonBalls(&player.cannonBalls),
+			scriptTiles: player.scriptTiles.clone(),
+		}
 	}
 }
 
@@ -118,6 +146,7 @@ pub struct Player<'a> {
 	velocity: Vector,
 	groundVelocity: Vector,
     position: Vector,
+	lastPosition: Vector,
 	hitbox: Rect,
 	renderPosition: Rect,
 	attackTimer: u32,
@@ -125,7 +154,6 @@ pub struct Player<'a> {
 	health: i32,
 	iframes: u32,
 	sword: Sprites<'a>,
-	healthSprites: Sprites<'a>,
 	hitSwitchLastFrame: bool,
 	keys: u8,
 	abyss: u16,
@@ -138,6 +166,12 @@ pub struct Player<'a> {
     cannon: bool,
 	cannonballSprites: Sprites<'a>,
 	cannonBalls: [Option<CannonBall>; 3],
+	/// Script tiles touched in the last step, to tell entering from staying
+	scriptTiles: Vec<((u16, u16), ScriptName)>,
+	onCheckpoint: bool,
+	/// Steps left of the death animation, the level ends when it runs out
+	dying: u16,
+	held: HeldOrder,
 }
 #[derive(Debug)]
 pub struct PlayerData {
@@ -149,6 +183,8 @@ pub struct PlayerData {
 	burn: bool,
 	abyss: bool,
 	cannon: bool,
+	scriptTiles: Vec<((u16, u16), ScriptName)>,
+	checkpoint: bool,
 }
 
 impl PlayerData {
@@ -168,7 +204,8 @@ impl PlayerData {
 					| CollisionType::SwitchTriggerGen(..) 
 					| CollisionType::KeyBlock 
 					| CollisionType::SwitchImmune 
-					| CollisionType::SwitchToggleGateAbyss(..) => {
+					| CollisionType::SwitchToggleGateAbyss(..)
+					| CollisionType::Sign => {
 					let eject = MapMod::blockCollide(location, tmp, map);
 					self.nextPos += eject;
 					tmp.reposition(self.nextPos + Vector(2f32, 2f32));
@@ -198,8 +235,16 @@ impl PlayerData {
 				CollisionType::Key => {
 					po.spawnTile(Tile::default(), location);
 					self.keys += 1;
+					po.publish(Topic::KeyPickedUp, self.keys as i32, player.id.getID());
+					po.playSound(Sound::KeyPickup);
 				}
-				CollisionType::SpawnGate(location) => po.spawnTiles(Tile::gate(), (location.0, location.1), (location.2, location.3)),
+				CollisionType::SpawnGate(location) => {
+					// The trigger stays in place, so only the first step on it is audible
+					if map.getScreen(map.getActiveScreenId()).unwrap().getTile((location.0, location.1)).getId() != Tile::gate().getId() {
+						po.playSound(Sound::GateSpawn);
+					}
+					po.spawnTiles(Tile::gate(), (location.0, location.1), (location.2, location.3));
+				},
 				CollisionType::ClearTiles(location) => po.spawnTiles(Tile::default(), (location.0, location.1), (location.2, location.3)),
 				CollisionType::Health => {
 					self.dmg += 25;
@@ -213,9 +258,23 @@ impl PlayerData {
 					po.spawnTile(Tile::default(), location);
 				},
 				CollisionType::Win => po.win(),
+				CollisionType::Script(name) => self.scriptTiles.push((location, name)),
+				CollisionType::Checkpoint => self.checkpoint = true,
 				_ => (),
 			}
 		}
+		// The cannon sword does not hit tiles, but it still reads signs and sets off script tiles
+		if player.attackTimer == 21 {
+			let tmp = relTupleToRect(player.getReach(), (self.nextPos + Vector(2f32, 2f32)).into());
+			let mut iter = map.calculateCollisionBounds(tmp);
+			while let Some((location, tile)) = map.collide(&mut iter) {
+				match tile.getCollisionType() {
+					CollisionType::Script(name) => po.tileEvent(name, TileEvent::Sword, location),
+					CollisionType::Sign => po.readSign(location),
+					_ => (),
+				}
+			}
+		}
 		if (player.attacking || player.attackTimer > 0) && !player.cannon {
 			let tmp = relTupleToRect(player.getSwordCollision(), (self.nextPos + Vector(2f32, 2f32)).into());
 			let mut iter = map.calculateCollisionBounds(tmp);
@@ -246,6 +305,7 @@ impl PlayerData {
 						};
 						po.spawnTile(Tile::new(id, tile.getCollisionType()), location);
 						self.stopHitSwitch = false;
+						po.publish(Topic::SwitchToggled, 0, player.id.getID());
 					},
 					CollisionType::SwitchTriggerGen(id) => {
 						let tileId = match tile.getId() {
@@ -255,6 +315,7 @@ impl PlayerData {
 						};
 						po.spawnTile(Tile::new(tileId, tile.getCollisionType()), location);
 						self.stopHitSwitch = false;
+						po.publish(Topic::SwitchToggled, 0, player.id.getID());
 						po.sendCounterMsg(Envelope::new(CounterMsg(i32::MIN), id, player.id.getID()));
 					},
 					CollisionType::SwitchToggleGateAbyss(range) => {
@@ -278,6 +339,7 @@ impl PlayerData {
 						};
 						po.spawnTile(Tile::new(id, tile.getCollisionType()), location);
 						self.stopHitSwitch = false;
+						po.publish(Topic::SwitchToggled, 0, player.id.getID());
 					}
 					_ => (),
 				}
@@ -309,9 +371,10 @@ impl PlayerData {
 		}
 
 		if let Some(boss) = player.snakeBoss {
-			let snakeBoss = po.getCtx().getHolder().getTyped(TypedID::<SnakeBoss>::new(boss)).unwrap();
-			if snakeBoss.collides(self.nextPos + Vector(25f32, 25f32)) {
-				po.sendCollisionMsg(Envelope::new(CollisionMsg::Damage(20), player.id.getID(), boss));
+			if let Some(snakeBoss) = po.getCtx().getHolder().getTyped(TypedID::<SnakeBoss>::new(boss)) {
+				if snakeBoss.collides(self.nextPos + Vector(25f32, 25f32)) {
+					po.sendCollisionMsg(Envelope::new(CollisionMsg::Damage(20), player.id.getID(), boss));
+				}
 			}
 		}
 
@@ -320,7 +383,7 @@ impl PlayerData {
 }
 
 impl<'a> Player<'a> {
-    pub fn new(creator: &'a TextureCreator<WindowContext>, positionX: f32, positionY: f32) -> io::Result<BoxCode<'a>> {
+    pub fn new<C>(creator: &'a TextureCreator<C>, positionX: f32, positionY: f32) -> io::Result<BoxCode<'a>> {
         let (direction, velocity, position, timer, idle, attackTimer, attacking, health, iframes, hitSwitchLastFrame, keys, abyss, respawn, burn, elevated, maybeBurn, maybeAbyss, snakeBoss, groundVelocity, cannon, cannonBalls) = (
             Direction::Down, 
             Vector(0f32, 0f32), 
@@ -346,7 +409,6 @@ impl<'a> Player<'a> {
         );
 		let animations = Animations::new("Resources/Images/Ninja.anim", NAMES, creator)?;
 		let sword = Sprites::new(creator, SWORD_FRAMES)?;
-		let healthSprites = Sprites::new(creator, HEALTH_FRAMES)?;
         let cannonballSprites = Sprites::new(creator, CANNONBALL)?;
 		let renderPosition = Rect::new(positionX.round() as i32, positionY.round() as i32, 50, 50);
 		let hitbox = Rect::new(positionX.round() as i32 + 2, positionY as i32 + 2, 46, 46);
@@ -354,7 +416,7 @@ impl<'a> Player<'a> {
         Ok(
 			BoxCode::Player(
 				Entity::new(
-					Player {id: TypedID::new(ID::empty()), animations, direction, velocity, position, timer, idle, hitbox, renderPosition, attackTimer, sword, attacking, health, iframes, healthSprites, hitSwitchLastFrame, keys, abyss, respawn, burn, elevated, maybeAbyss, maybeBurn, snakeBoss, groundVelocity, cannon, cannonballSprites, cannonBalls},
+					Player {id: TypedID::new(ID::empty()), animations, direction, velocity, position, lastPosition: position, timer, idle, hitbox, renderPosition, attackTimer, sword, attacking, health, iframes, hitSwitchLastFrame, keys, abyss, respawn, burn, elevated, maybeAbyss, maybeBurn, snakeBoss, groundVelocity, cannon, cannonballSprites, cannonBalls, scriptTiles: vec![], onCheckpoint: false, dying: 0, held: HeldOrder::default()},
 					PlayerData {
 						keys,
 						nextPos: position,
@@ -363,12 +425,14 @@ impl<'a> Player<'a> {
 						abyss: false,
 						burn: false,
 						cannon: false,
+						scriptTiles: vec![],
+						checkpoint: false,
 					},
 				)
 			)
 		)
     }
-	pub fn fromInner(inner: InnerPlayer, creator: &'a TextureCreator<WindowContext>) -> io::Result<BoxCode<'a>> {
+	pub fn fromInner<C>(inner: InnerPlayer, creator: &'a TextureCreator<C>) -> io::Result<BoxCode<'a>> {
 		Ok(BoxCode::Player(
 			Entity::new(
 				Player {
@@ -377,6 +441,7 @@ impl<'a> Player<'a> {
 					direction: inner.direction,
 					velocity: inner.velocity,
 					position: inner.position,
+					lastPosition: inner.position,
 					timer: inner.timer,
 					idle: inner.idle,
 					hitbox: Rect::from(inner.hitbox),
@@ -385,30 +450,36 @@ impl<'a> Player<'a> {
 					renderPosition: Rect::from(inner.renderPosition),
 					iframes: inner.iframes,
 					sword: Sprites::new(creator, SWORD_FRAMES)?,
-					healthSprites: Sprites::new(creator, HEALTH_FRAMES)?,
 					attacking: inner.attacking,
 					hitSwitchLastFrame: false,
-					keys: 0,
-					abyss: 0,
-					respawn: Vector(0f32, 0f32),
-					burn: 0,
-					elevated: 0,
+					keys: inner.keys,
+					abyss: inner.abyss,
+					respawn: inner.respawn,
+					burn: inner.burn,
+					elevated: inner.elevated,
 					maybeAbyss: false,
 					maybeBurn: false,
-					snakeBoss: None,
+					snakeBoss: inner.snakeBoss,
 					groundVelocity: Vector(0f32, 0f32),
-					cannon: false,
+					cannon: inner.cannon,
 					cannonballSprites: Sprites::new(creator, CANNONBALL)?,
-					cannonBalls: [None, None, None],
+					cannonBalls: CannonBall::fromInners(inner.cannonBalls),
+					scriptTiles: inner.scriptTiles.clone(),
+					onCheckpoint: inner.onCheckpoint,
+					dying: inner.dying,
+					// Keys held when the state was saved are not held any more on loading it
+					held: HeldOrder::default(),
 				},
 				PlayerData {
-					keys: 0,
+					keys: inner.keys,
 					nextPos: inner.position,
 					stopHitSwitch: true,
 					dmg: 0,
 					abyss: false,
 					burn: false,
-					cannon: false,
+					cannon: inner.cannon,
+					scriptTiles: inner.scriptTiles,
+					checkpoint: inner.onCheckpoint,
 				}
 			)
 		))
@@ -418,8 +489,11 @@ impl<'a> Player<'a> {
 	}
 
 	fn getSwordCollision(&self) -> (i32, i32, u32, u32) {
+		if self.cannon {(0, 0, 0, 0)} else {self.getReach()}
+	}
+	/// Where a swing lands in front of the player, with either sword. Signs and script tiles are hit through this.
+	fn getReach(&self) -> (i32, i32, u32, u32) {
 		match self.direction {
-			_ if self.cannon => (0, 0, 0, 0),
 			Direction::Up => SWORD_UP_COLLISION,
 			Direction::Down => SWORD_DOWN_COLLISION,
 			Direction::Left => SWORD_LEFT_COLLISION,
@@ -461,10 +535,12 @@ impl<'a> Player<'a> {
 	}
 
 	pub fn transition(&mut self, ctx: &mut GameContext) -> bool {
-		if let Some(hitbox) = ctx.getMapMut().transitionScreen(self.hitbox) {
+		if let Some(hitbox) = ctx.transitionScreen(self.hitbox) {
 			let point: (i32, i32) = hitbox.top_left().into();
 			self.position = Vector::from(point);
 			self.updatePositionsCtx(ctx);
+			// Tiles of the old screen are left behind without an exit event
+			self.scriptTiles.clear();
 			true
 		}
 		else {false}
@@ -472,63 +548,120 @@ impl<'a> Player<'a> {
 	}
 
     pub fn signal(&mut self, signal: Signals) {
-		match (signal.attack, self.attacking) {
-			(Some(true), false) => {
-				self.attackTimer = 21;
-				self.attacking = true;
-				self.idle = false;
-			},
-			(Some(false), true) => {
-				self.attacking = false;
-			},
-			(Some(true), true) | (None, _) | (Some(false), false) => {},
+		// A press counts even if the key was let go again before this step
+		if signal.attackPressed || (signal.attack == Some(true) && !self.attacking) {
+			self.attackTimer = 21;
+			self.attacking = true;
+			self.idle = false;
+		}
+		if signal.attack == Some(false) {
+			self.attacking = false;
+		}
+		self.held.update(signal);
+		if let Some(direction) = self.held.latest() {
+			self.direction = direction;
+		}
+		let (x, y) = self.held.axes();
+		// Diagonals move at the same speed as straight lines
+		let speed = if x != 0 && y != 0 {SPEED / 2f32.sqrt()} else {SPEED};
+		self.velocity = Vector(x as f32 * speed, y as f32 * speed);
+	}
+	fn drawAt(&self, canvas: &mut dyn Renderer, renderPosition: Rect, alpha: Option<f32>) {
+		if self.dying > 0 {
+			let mut tmp = renderPosition;
+			tmp.resize(50 * self.dying as u32 / DEATH_STEPS as u32, 50 * self.dying as u32 / DEATH_STEPS as u32);
+			tmp.center_on(renderPosition.center());
+			self.animations.drawNextFrame(canvas, tmp);
+			return;
 		}
-		match signal {
-			Signals {up: Some(true), ..} => {
-				self.direction = Direction::Up;
-				self.velocity.1 = -3f32;
-			},
-			Signals {down: Some(true), ..} => {
-				self.direction = Direction::Down;
-				self.velocity.1 = 3f32;
-			},
-			Signals {up: Some(false), ..} => {
-				self.velocity.1 = 0f32;
-			},
-			Signals {down: Some(false), ..} => {
-				self.velocity.1 = 0f32;
-			},
-			_ => (),
+		if (self.iframes / 10) % 2 == 1 {return;}
+		if self.abyss > 0 {
+			let mut tmp = renderPosition;
+			tmp.resize(50 * self.abyss as u32 / 30, 50 * self.abyss as u32 / 30);
+			tmp.center_on(renderPosition.center());
+			self.animations.drawNextFrame(canvas, tmp);
+			return;
 		}
-		match signal {
-			Signals {left: Some(true), ..} => {
-				self.direction = Direction::Left;
-				self.velocity.0 = -3f32;
-			},
-			Signals {right: Some(true), ..} => {
-				self.direction = Direction::Right;
-				self.velocity.0 = 3f32;
-			},
-			Signals {left: Some(false), ..} => {
-				self.velocity.0 = 0f32;
-			},
-			Signals {right: Some(false), ..} => {
-				self.velocity.0 = 0f32;
-			},
-			_ => (),
+		if self.burn > 360 {
+			if self.burn > 370 {
+				self.animations.drawNextFrame(canvas, renderPosition);
+			}
+			return;
+		}
+		if self.attacking || self.attackTimer > 0 {
+			let idx = if self.cannon {
+				match self.direction {
+					Direction::Up => 1,
+					Direction::Down => 4,
+					Direction::Left => 2,
+					Direction::Right => 3,
+				}
+			} else {0};
+			match self.direction {
+				Direction::Up => {
+					self.sword.getSprite(idx).draw(canvas, Rect::new (
+						SWORD_UP.0 + renderPosition.x(),
+						SWORD_UP.1 + renderPosition.y(),
+						SWORD_UP.2,
+						SWORD_UP.3
+					), false, false);
+					self.animations.drawNextFrame(canvas, renderPosition);
+				},
+				Direction::Down => {
+					self.animations.drawNextFrame(canvas, renderPosition);
+					self.sword.getSprite(idx).draw(canvas, Rect::new(
+						SWORD_DOWN.0 + renderPosition.x(),
+						SWORD_DOWN.1 + renderPosition.y(),
+						SWORD_DOWN.2,
+						SWORD_DOWN.3
+					), false, true);
+				},
+				Direction::Left => {
+					self.sword.getSprite(idx).draw(canvas, Rect::new (
+						SWORD_LEFT.0 + renderPosition.x(),
+						SWORD_LEFT.1 + renderPosition.y(),
+						SWORD_LEFT.2,
+						SWORD_LEFT.3
+					), false, false);
+					self.animations.drawNextFrame(canvas, renderPosition);
+				},
+				Direction::Right => {
+					self.sword.getSprite(idx).draw(canvas, Rect::new (
+						SWORD_RIGHT.0 + renderPosition.x(),
+						SWORD_RIGHT.1 + renderPosition.y(),
+						SWORD_RIGHT.2,
+						SWORD_RIGHT.3
+					), false, false);
+					self.animations.drawNextFrame(canvas, renderPosition);
+						
+				}
+			}
+		}
+		else {
+			self.animations.drawNextFrame(canvas, renderPosition);
+		}
+		for ball in self.cannonBalls.iter().filter_map(|e| e.as_ref()) {
+			self.cannonballSprites.getSprite(0).draw(canvas, ball.drawPosition(alpha), false, false);
 		}
 	}
 	pub fn getPosition(&self) -> Vector {
 		self.position
 	}
+	pub fn getHealth(&self) -> i32 {
+		self.health
+	}
+	pub fn getKeys(&self) -> u8 {
+		self.keys
+	}
 	pub fn getCenter(&self) -> Vector {
 		Vector::from(<Point as Into<(i32, i32)>>::into(self.hitbox.center()))
 	}
-	pub fn informSnakeBoss(&mut self, id: ID) {
-		self.snakeBoss = Some(id);
+	pub fn hasCannon(&self) -> bool {
+		self.cannon
 	}
-	pub fn informSnakeBossDeath(&mut self) {
-		self.snakeBoss = None;
+	/// The boss on this level, once it has made itself known.
+	pub fn getSnakeBoss(&self) -> Option<ID> {
+		self.snakeBoss
 	}
 	pub fn isActivateSnakeBoss(&self) -> bool {
 		self.position.1 <= 500f32
@@ -549,6 +682,7 @@ impl<'a> Collision for Player<'a> {
 				else if self.iframes == 0 && recv.getSubID() == 0 {
 					self.health -= dmg;
 					self.iframes = 90;
+					po.playSound(Sound::Hit);
 				}
 			},
 			CollisionMsg::Ground(hitbox, dp) => {
@@ -571,9 +705,22 @@ impl<'a> Collision for Player<'a> {
 
 impl<'a> Counter for Player<'a> {}
 impl<'a> RegisterID for Player<'a> {}
+impl<'a> Subscriber for Player<'a> {
+	fn notify(&mut self, msg: Envelope<TopicMsg>, po: &PO) {
+		match msg.getMsg().topic {
+			// Scripts can publish this too, without a boss behind it
+			Topic::BossActivated => if po.getCtx().getHolder().getTyped(TypedID::<SnakeBoss>::new(msg.getSender())).is_some() {
+				self.snakeBoss = Some(msg.getSender());
+			},
+			Topic::BossDied => self.snakeBoss = None,
+			_ => (),
+		}
+	}
+}
 
 impl<'a> EntityTraitsWrappable<'a> for Player<'a> {
 	type Data = PlayerData;
+	fn drawDepth(&self) -> Option<i32> {Some(self.hitbox.bottom())}
 	fn setID(&mut self, id: TypedID<'a, Self>) {
 		self.id = id;
 	}
@@ -589,7 +736,9 @@ impl<'a> EntityTraitsWrappable<'a> for Player<'a> {
 		//data.transition = ctx.getMap().transitionScreen(self.hitbox);
 		data.abyss = false;
 		data.burn = false;
-		if self.abyss > 0 || self.burn > 360 {
+		data.scriptTiles.clear();
+		data.checkpoint = false;
+		if self.abyss > 0 || self.burn > 360 || self.dying > 0 {
 			data.nextPos = Vector(0f32, 0f32);
 			return key;
 		}
@@ -606,7 +755,18 @@ impl<'a> EntityTraitsWrappable<'a> for Player<'a> {
 		key
 	}
 	fn update(&mut self, data: &Self::Data, po: &mut PO) {
-		if self.health <= 0 {po.die();}
+		self.lastPosition = self.position;
+		if self.dying > 0 {
+			self.dying -= 1;
+			if self.dying == 0 {po.die();}
+			return;
+		}
+		if self.health <= 0 {
+			self.dying = DEATH_STEPS;
+			return;
+		}
+		if data.checkpoint && !self.onCheckpoint {po.reachCheckpoint();}
+		self.onCheckpoint = data.checkpoint;
 		if data.cannon {self.cannon = true;}
 		if data.abyss && self.maybeAbyss && self.elevated == 0 {
 			self.abyss = 31;
@@ -621,6 +781,14 @@ impl<'a> EntityTraitsWrappable<'a> for Player<'a> {
 		}
 		self.maybeAbyss = data.abyss;
 		self.maybeBurn = data.burn;
+		for &(location, name) in data.scriptTiles.iter() {
+			let event = if self.scriptTiles.contains(&(location, name)) {TileEvent::Stay} else {TileEvent::Enter};
+			po.tileEvent(name, event, location);
+		}
+		for &(location, name) in self.scriptTiles.iter().filter(|tile| !data.scriptTiles.contains(tile)) {
+			po.tileEvent(name, TileEvent::Exit, location);
+		}
+		self.scriptTiles.clone_from(&data.scriptTiles);
 		self.position += data.nextPos;
 		self.updatePositionsPO(po);
 		if self.abyss > 0 {
@@ -662,6 +830,7 @@ impl<'a> EntityTraitsWrappable<'a> for Player<'a> {
 			self.health += data.dmg;
 			if data.dmg < 0 {
 				self.iframes = 90;
+				po.playSound(Sound::Hit);
 			}
 			if self.health > 50 {
 				self.health = 50;
@@ -682,6 +851,9 @@ impl<'a> EntityTraitsWrappable<'a> for Player<'a> {
 			self.timer = 0;
 			self.animations.update();
 		}
+		if self.attackTimer == 21 {
+			po.playSound(if self.cannon {Sound::CannonFire} else {Sound::SwordSwing});
+		}
 		if self.attackTimer == 21 && self.cannon {
 			if let Some((i, _)) = self.cannonBalls.iter().enumerate().filter(|b| b.1.is_none()).next() {
 				let velocity = match self.direction {
@@ -730,92 +902,13 @@ impl<'a> EntityTraitsWrappable<'a> for Player<'a> {
 	}
 	fn needsExecution(&self) -> bool {true}
 	fn tick(&mut self) {}
-	fn draw(&self, canvas: &mut Canvas<Window>) {
-		let mut health = self.health;
-		let mut healthRect = Rect::new(15, 15, 15, 15);
-		for _ in 0..5 {
-			if health >= 10 {
-				health -= 10;
-				self.healthSprites.getSprite(HEALTH_IDX::Full as usize)
-			}
-			else if health > 0 {
-				health -= 10;
-				self.healthSprites.getSprite(HEALTH_IDX::Half as usize)
-			}
-			else {
-				self.healthSprites.getSprite(HEALTH_IDX::Empty as usize)
-			}.draw(canvas, healthRect, false, false);
-			healthRect.reposition((healthRect.x() + 15, healthRect.y()));
-		}
-		if (self.iframes / 10) % 2 == 1 {return;}
-		if self.abyss > 0 {
-			let mut tmp = self.renderPosition;
-			tmp.resize(50 * self.abyss as u32 / 30, 50 * self.abyss as u32 / 30);
-			tmp.center_on(self.renderPosition.center());
-			self.animations.drawNextFrame(canvas, tmp);
-			return;
-		}
-		if self.burn > 360 {
-			if self.burn > 370 {
-				self.animations.drawNextFrame(canvas, self.renderPosition);
-			}
-			return;
-		}
-		if self.attacking || self.attackTimer > 0 {
-			let idx = if self.cannon {
-				match self.direction {
-					Direction::Up => 1,
-					Direction::Down => 4,
-					Direction::Left => 2,
-					Direction::Right => 3,
-				}
-			} else {0};
-			match self.direction {
-				Direction::Up => {
-					self.sword.getSprite(idx).draw(canvas, Rect::new (
-						SWORD_UP.0 + self.renderPosition.x(),
-						SWORD_UP.1 + self.renderPosition.y(),
-						SWORD_UP.2,
-						SWORD_UP.3
-					), false, false);
-					self.animations.drawNextFrame(canvas, self.renderPosition);
-				},
-				Direction::Down => {
-					self.animations.drawNextFrame(canvas, self.renderPosition);
-					self.sword.getSprite(idx).draw(canvas, Rect::new(
-						SWORD_DOWN.0 + self.renderPosition.x(),
-						SWORD_DOWN.1 + self.renderPosition.y(),
-						SWORD_DOWN.2,
-						SWORD_DOWN.3
-					), false, true);
-				},
-				Direction::Left => {
-					self.sword.getSprite(idx).draw(canvas, Rect::new (
-						SWORD_LEFT.0 + self.renderPosition.x(),
-						SWORD_LEFT.1 + self.renderPosition.y(),
-						SWORD_LEFT.2,
-						SWORD_LEFT.3
-					), false, false);
-					self.animations.drawNextFrame(canvas, self.renderPosition);
-				},
-				Direction::Right => {
-					self.sword.getSprite(idx).draw(canvas, Rect::new (
-						SWORD_RIGHT.0 + self.renderPosition.x(),
-						SWORD_RIGHT.1 + self.renderPosition.y(),
-						SWORD_RIGHT.2,
-						SWORD_RIGHT.3
-					), false, false);
-					self.animations.drawNextFrame(canvas, self.renderPosition);
-						
-				}
-			}
-		}
-		else {
-			self.animations.drawNextFrame(canvas, self.renderPosition);
-		}
-		for ball in self.cannonBalls.iter().filter_map(|e| e.as_ref()) {
-			self.cannonballSprites.getSprite(0).draw(canvas, ball.renderPosition, false, false);
-		}
+	fn draw(&self, canvas: &mut dyn Renderer) {
+		self.drawAt(canvas, self.renderPosition, None);
+	}
+	fn drawInterpolated(&self, canvas: &mut dyn Renderer, alpha: f32) {
+		let mut renderPosition = self.renderPosition;
+		renderPosition.reposition(Common::interpolate(self.lastPosition, self.position, alpha));
+		self.drawAt(canvas, renderPosition, Some(alpha));
 	}
 
 }
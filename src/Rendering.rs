@@ -0,0 +1,104 @@
+use sdl2::render::{Canvas, RenderTarget};
+use sdl2::rect::{Rect, Point};
+use sdl2::pixels::Color;
+
+use crate::SpriteLoader::Sprite;
+
+/// Anything the game can be drawn onto. All draw paths go through this so the same
+/// entity and map code can target a window, an offscreen surface or a recording.
+pub trait Renderer {
+	fn drawSprite(&mut self, sprite: &Sprite, quad: Rect, rot: f64, center: Option<Point>, flipHorizontal: bool, flipVertical: bool);
+	fn drawRect(&mut self, rect: Rect, color: Color);
+	/// Where the view starts in screen coordinates. Add this to draw something fixed to the window.
+	fn getOffset(&self) -> Point {Point::new(0, 0)}
+}
+
+/// Draws onto `inner` shifted by `-offset`, so entities can keep drawing in screen coordinates
+/// while the camera scrolls.
+pub struct OffsetRenderer<'r> {
+	inner: &'r mut dyn Renderer,
+	offset: Point,
+}
+
+impl<'r> OffsetRenderer<'r> {
+	pub fn new(inner: &'r mut dyn Renderer, offset: Point) -> OffsetRenderer<'r> {
+		OffsetRenderer {inner, offset}
+	}
+	fn shift(&self, mut rect: Rect) -> Rect {
+		rect.offset(-self.offset.x, -self.offset.y);
+		rect
+	}
+}
+
+impl<'r> Renderer for OffsetRenderer<'r> {
+	fn drawSprite(&mut self, sprite: &Sprite, quad: Rect, rot: f64, center: Option<Point>, flipHorizontal: bool, flipVertical: bool) {
+		self.inner.drawSprite(sprite, self.shift(quad), rot, center, flipHorizontal, flipVertical);
+	}
+	fn drawRect(&mut self, rect: Rect, color: Color) {
+		self.inner.drawRect(self.shift(rect), color);
+	}
+	fn getOffset(&self) -> Point {
+		self.inner.getOffset() + self.offset
+	}
+}
+
+// Covers both the window canvas and the software `Canvas<Surface>`
+impl<T: RenderTarget> Renderer for Canvas<T> {
+	#[allow(unused_must_use)]
+	fn drawSprite(&mut self, sprite: &Sprite, quad: Rect, rot: f64, center: Option<Point>, flipHorizontal: bool, flipVertical: bool) {
+		self.copy_ex(sprite.getTexture(), None, quad, rot, center, flipHorizontal, flipVertical);
+	}
+	#[allow(unused_must_use)]
+	fn drawRect(&mut self, rect: Rect, color: Color) {
+		let prevColor = self.draw_color();
+		self.set_draw_color(color);
+		self.draw_rect(rect);
+		self.set_draw_color(prevColor);
+	}
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DrawCall {
+	Sprite {
+		sprite: String,
+		rect: Rect,
+		flip: (bool, bool),
+		rotation: f64,
+	},
+	Rect {
+		rect: Rect,
+		color: Color,
+	},
+}
+
+/// Renderer that only logs what would have been drawn, in order.
+#[derive(Default)]
+pub struct DrawRecorder {
+	calls: Vec<DrawCall>,
+}
+
+impl DrawRecorder {
+	pub fn new() -> DrawRecorder {
+		DrawRecorder {calls: vec![]}
+	}
+	pub fn getCalls(&self) -> &[DrawCall] {
+		&self.calls
+	}
+	pub fn clear(&mut self) {
+		self.calls.clear();
+	}
+}
+
+impl Renderer for DrawRecorder {
+	fn drawSprite(&mut self, sprite: &Sprite, quad: Rect, rot: f64, _center: Option<Point>, flipHorizontal: bool, flipVertical: bool) {
+		self.calls.push(DrawCall::Sprite {
+			sprite: sprite.getName().to_string(),
+			rect: quad,
+			flip: (flipHorizontal, flipVertical),
+			rotation: rot,
+		});
+	}
+	fn drawRect(&mut self, rect: Rect, color: Color) {
+		self.calls.push(DrawCall::Rect {rect, color});
+	}
+}
@@ -0,0 +1,120 @@
+use serde::{Serialize, Deserialize};
+use serde_json::Deserializer;
+
+use std::io;
+use std::fs::File;
+
+use crate::Signals;
+
+/// Input for a run of one level, started fresh from `map` with `seed`. Saved as JSON with the
+/// seed, the map, the inputs as `[step, signals]` pairs and the number of steps. Only steps that carried input are stored.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Replay {
+	pub seed: u64,
+	pub map: String,
+	inputs: Vec<(u64, Signals)>,
+	/// Steps recorded, including those without input after the last one
+	#[serde(default)]
+	steps: u64,
+}
+
+impl Replay {
+	pub fn new(seed: u64, map: &str) -> Replay {
+		Replay {seed, map: map.to_string(), inputs: vec![], steps: 0}
+	}
+	pub fn load(filename: &str) -> io::Result<Replay> {
+		let mut deserializer = Deserializer::from_reader(File::open(filename)?);
+		Ok(Replay::deserialize(&mut deserializer)?)
+	}
+	pub fn save(&self, filename: &str) -> io::Result<()> {
+		serde_json::to_writer(File::create(filename)?, self)?;
+		Ok(())
+	}
+}
+
+pub struct ReplayRecorder {
+	replay: Replay,
+	frame: u64,
+}
+
+impl ReplayRecorder {
+	pub fn new(seed: u64, map: &str) -> ReplayRecorder {
+		ReplayRecorder {replay: Replay::new(seed, map), frame: 0}
+	}
+	/// Call once per simulation step with the signals given to the player.
+	pub fn record(&mut self, signals: Signals) {
+		if !signals.isEmpty() {
+			self.replay.inputs.push((self.frame, signals));
+		}
+		self.frame += 1;
+		self.replay.steps = self.frame;
+	}
+	pub fn getReplay(&self) -> &Replay {
+		&self.replay
+	}
+}
+
+pub struct ReplayPlayer {
+	replay: Replay,
+	frame: u64,
+	next: usize,
+}
+
+impl ReplayPlayer {
+	pub fn new(replay: Replay) -> ReplayPlayer {
+		ReplayPlayer {replay, frame: 0, next: 0}
+	}
+	/// Signals for the next simulation step.
+	pub fn next(&mut self) -> Signals {
+		let mut signals = Signals::default();
+		if let Some(&(frame, recorded)) = self.replay.inputs.get(self.next) {
+			if frame == self.frame {
+				signals = recorded;
+				self.next += 1;
+			}
+		}
+		self.frame += 1;
+		signals
+	}
+	/// Whether every recorded step has been played.
+	pub fn isFinished(&self) -> bool {
+		self.next >= self.replay.inputs.len() && self.frame >= self.replay.steps
+	}
+	pub fn getFrame(&self) -> u64 {
+		self.frame
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn press(step: u64) -> Signals {
+		Signals {down: Some(step % 2 == 0), attack: Some(true), attackPressed: step % 3 == 0, ..Signals::default()}
+	}
+
+	#[test]
+	fn playsBackWhatWasRecorded() {
+		let steps: Vec<Signals> = (0..20).map(|step| if step % 4 == 1 {press(step)} else {Signals::default()}).collect();
+		let mut recorder = ReplayRecorder::new(99, "Resources/Map1.mp");
+		for &signals in steps.iter() {
+			recorder.record(signals);
+		}
+		// Empty steps are not stored
+		assert_eq!(recorder.getReplay().inputs.len(), 5);
+
+		let json = serde_json::to_string(recorder.getReplay()).unwrap();
+		let replay: Replay = serde_json::from_str(&json).unwrap();
+		assert_eq!((replay.seed, replay.map.as_str()), (99, "Resources/Map1.mp"));
+
+		let mut player = ReplayPlayer::new(replay);
+		for signals in steps {
+			assert!(!player.isFinished());
+			let played = player.next();
+			assert_eq!((played.up, played.down, played.left, played.right, played.attack, played.attackPressed),
+				(signals.up, signals.down, signals.left, signals.right, signals.attack, signals.attackPressed));
+		}
+		assert!(player.isFinished());
+		assert_eq!(player.getFrame(), 20);
+	}
+}
@@ -0,0 +1,81 @@
+use rand::{RngCore, Error};
+use rand::rngs::OsRng;
+
+use serde::{Serialize, Deserialize};
+
+/// SplitMix64 generator. Small enough to serialize with the game state so
+/// a run can be reproduced from its seed or resumed from a save.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct GameRng {
+	seed: u64,
+	state: u64,
+}
+
+impl GameRng {
+	pub fn new(seed: u64) -> GameRng {
+		GameRng {seed, state: seed}
+	}
+	pub fn fromEntropy() -> GameRng {
+		GameRng::new(OsRng.next_u64())
+	}
+	/// The seed this generator was created with, not its current state.
+	pub fn getSeed(&self) -> u64 {
+		self.seed
+	}
+}
+
+impl RngCore for GameRng {
+	fn next_u64(&mut self) -> u64 {
+		self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+		let mut z = self.state;
+		z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+		z ^ (z >> 31)
+	}
+	fn next_u32(&mut self) -> u32 {
+		(self.next_u64() >> 32) as u32
+	}
+	fn fill_bytes(&mut self, dest: &mut [u8]) {
+		for chunk in dest.chunks_mut(8) {
+			let bytes = self.next_u64().to_le_bytes();
+			chunk.copy_from_slice(&bytes[..chunk.len()]);
+		}
+	}
+	fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+		self.fill_bytes(dest);
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn take(rng: &mut GameRng, count: usize) -> Vec<u64> {
+		(0..count).map(|_| rng.next_u64()).collect()
+	}
+
+	#[test]
+	fn sameSeedSameSequence() {
+		assert_eq!(take(&mut GameRng::new(42), 16), take(&mut GameRng::new(42), 16));
+		assert_ne!(take(&mut GameRng::new(42), 16), take(&mut GameRng::new(43), 16));
+	}
+
+	#[test]
+	fn resumesFromSavedState() {
+		let mut rng = GameRng::new(7);
+		take(&mut rng, 5);
+		let mut saved: GameRng = serde_json::from_str(&serde_json::to_string(&rng).unwrap()).unwrap();
+		assert_eq!(saved.getSeed(), 7);
+		assert_eq!(take(&mut saved, 16), take(&mut rng, 16));
+	}
+
+	#[test]
+	fn fillBytesHandlesPartialChunks() {
+		let mut bytes = [0u8; 11];
+		GameRng::new(3).fill_bytes(&mut bytes);
+		let mut rng = GameRng::new(3);
+		let expected: Vec<u8> = rng.next_u64().to_le_bytes().iter().chain(rng.next_u64().to_le_bytes()[..3].iter()).copied().collect();
+		assert_eq!(&bytes[..], &expected[..]);
+	}
+}
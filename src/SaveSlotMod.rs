@@ -0,0 +1,185 @@
+use sdl2::event::Event;
+use sdl2::keyboard::Scancode;
+
+use serde::{Serialize, Deserialize};
+use serde_json::Deserializer;
+
+use std::io;
+use std::fs::{self, File};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::GameContext;
+
+pub const SLOT_COUNT: usize = 4;
+const SLOT_DIR: &str = "Resources/Saves";
+const MAX_NAME_LEN: usize = 16;
+
+/// What the slot-select screen shows about a slot. The game itself lives in `slotSaveFile`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SlotInfo {
+	pub name: String,
+	pub level: usize,
+	/// Seconds
+	pub playTime: u64,
+	pub health: i32,
+	pub keys: u8,
+	/// Seconds since the unix epoch of the last write
+	pub timestamp: u64,
+}
+
+impl SlotInfo {
+	pub fn new(name: String) -> SlotInfo {
+		SlotInfo {name, level: 0, playTime: 0, health: 50, keys: 0, timestamp: now()}
+	}
+	pub fn load(slot: usize) -> io::Result<SlotInfo> {
+		let mut deserializer = Deserializer::from_reader(File::open(slotInfoFile(slot))?);
+		Ok(SlotInfo::deserialize(&mut deserializer)?)
+	}
+	pub fn save(&mut self, slot: usize) -> io::Result<()> {
+		fs::create_dir_all(SLOT_DIR)?;
+		self.timestamp = now();
+		serde_json::to_writer(File::create(slotInfoFile(slot))?, self)?;
+		Ok(())
+	}
+	/// Copy the player's current state into the slot.
+	pub fn update(&mut self, ctx: &GameContext, playTime: Duration) {
+		let player = ctx.getPlayer();
+		self.health = player.getHealth();
+		self.keys = player.getKeys();
+		self.playTime = playTime.as_secs();
+	}
+	fn describe(&self) -> String {
+		let age = now().saturating_sub(self.timestamp);
+		let age = match age {
+			0..=59 => String::from("just now"),
+			60..=3599 => format!("{} min ago", age / 60),
+			3600..=86399 => format!("{} h ago", age / 3600),
+			_ => format!("{} days ago", age / 86400),
+		};
+		format!("{}  Level {}  {}:{:02}:{:02}  Health {}  Keys {}  ({})",
+			self.name, self.level + 1, self.playTime / 3600, self.playTime / 60 % 60, self.playTime % 60, self.health, self.keys, age)
+	}
+}
+
+pub fn slotSaveFile(slot: usize) -> String {
+	format!("{}/slot{}.sav", SLOT_DIR, slot)
+}
+
+fn slotInfoFile(slot: usize) -> String {
+	format!("{}/slot{}.json", SLOT_DIR, slot)
+}
+
+/// Whether the slot has a mid-level save to continue from.
+pub fn hasSaveFile(slot: usize) -> bool {
+	Path::new(&slotSaveFile(slot)).exists()
+}
+
+/// Remove the mid-level save, e.g. once its level is won.
+pub fn clearSaveFile(slot: usize) -> io::Result<()> {
+	match fs::remove_file(slotSaveFile(slot)) {
+		Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+		_ => Ok(()),
+	}
+}
+
+fn deleteSlot(slot: usize) -> io::Result<()> {
+	clearSaveFile(slot)?;
+	match fs::remove_file(slotInfoFile(slot)) {
+		Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+		_ => Ok(()),
+	}
+}
+
+fn now() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// State of the startup menu. Up/Down choose a slot, Return picks it, Delete twice empties it.
+/// Picking an empty slot asks for a name first.
+pub struct SlotSelect {
+	slots: Vec<Option<SlotInfo>>,
+	selected: usize,
+	naming: Option<String>,
+	/// Delete was pressed once on the selected slot
+	deleting: bool,
+	choice: Option<usize>,
+}
+
+impl SlotSelect {
+	pub fn new() -> SlotSelect {
+		SlotSelect {
+			slots: (0..SLOT_COUNT).map(|slot| SlotInfo::load(slot).ok()).collect(),
+			selected: 0,
+			naming: None,
+			deleting: false,
+			choice: None,
+		}
+	}
+	pub fn handleEvent(&mut self, event: &Event) {
+		if let Some(ref mut name) = self.naming {
+			match event {
+				Event::TextInput{text, ..} if name.chars().count() < MAX_NAME_LEN => name.push_str(text),
+				Event::KeyDown{scancode: Some(Scancode::Backspace), ..} => {name.pop();},
+				Event::KeyDown{scancode: Some(Scancode::Escape), ..} => self.naming = None,
+				Event::KeyDown{scancode: Some(Scancode::Return), ..} if !name.trim().is_empty() => {
+					let mut info = SlotInfo::new(name.trim().to_string());
+					match info.save(self.selected) {
+						Ok(()) => {
+							self.slots[self.selected] = Some(info);
+							self.choice = Some(self.selected);
+						},
+						Err(e) => eprintln!("Could not create save slot: {}", e),
+					}
+					self.naming = None;
+				},
+				_ => (),
+			}
+			return;
+		}
+		if self.deleting {
+			match event {
+				Event::KeyDown{repeat: true, ..} => (),
+				Event::KeyDown{scancode: Some(Scancode::Delete), ..} => {
+					self.deleting = false;
+					match deleteSlot(self.selected) {
+						Ok(()) => self.slots[self.selected] = None,
+						Err(e) => eprintln!("Could not delete save slot: {}", e),
+					}
+				},
+				// Any other key keeps the slot
+				Event::KeyDown{..} => self.deleting = false,
+				_ => (),
+			}
+			return;
+		}
+		match event {
+			Event::KeyDown{scancode: Some(Scancode::Up), ..} => self.selected = (self.selected + SLOT_COUNT - 1) % SLOT_COUNT,
+			Event::KeyDown{scancode: Some(Scancode::Down), ..} => self.selected = (self.selected + 1) % SLOT_COUNT,
+			Event::KeyDown{scancode: Some(Scancode::Return), ..} => {
+				if self.slots[self.selected].is_some() {self.choice = Some(self.selected);}
+				else {self.naming = Some(String::new());}
+			},
+			Event::KeyDown{scancode: Some(Scancode::Delete), repeat: false, ..} => self.deleting = self.slots[self.selected].is_some(),
+			_ => (),
+		}
+	}
+	pub fn isNaming(&self) -> bool {
+		self.naming.is_some()
+	}
+	pub fn getChoice(&self) -> Option<usize> {
+		self.choice
+	}
+	/// One line of text per slot, plus whether it is highlighted.
+	pub fn lines(&self) -> Vec<(String, bool)> {
+		self.slots.iter().enumerate().map(|(i, slot)| {
+			let text = match (slot, &self.naming) {
+				(_, Some(name)) if i == self.selected => format!("{}. Name: {}_", i + 1, name),
+				(Some(info), _) if i == self.selected && self.deleting => format!("{}. Delete {}? Press Delete again to confirm", i + 1, info.name),
+				(Some(info), _) => format!("{}. {}", i + 1, info.describe()),
+				(None, _) => format!("{}. Empty", i + 1),
+			};
+			(text, i == self.selected)
+		}).collect()
+	}
+}
@@ -1,8 +1,16 @@
-use sdl2::render::Canvas;
-use sdl2::video::Window;
 use std::iter::Iterator;
 use std::cell::UnsafeCell;
+use std::ops::RangeBounds;
+use crate::Rendering::Renderer;
 use crate::{GameContext, ID, PO};
+use crate::Entities::Traits::EntityDyn;
+/// Default layer for entities.
+pub const GROUND_LAYER: i32 = 0;
+/// Cannons sit on this layer so they keep drawing over everything on the ground, as existing levels expect.
+pub const RAISED_LAYER: i32 = 1;
+/// Layers from this one upwards are drawn above the map's overlay tiles.
+pub const OVERLAY_LAYER: i32 = 10;
+
 pub struct Scheduler{}
 impl Scheduler {
 	pub fn new() -> Scheduler {Scheduler{}}
@@ -34,21 +42,32 @@ impl Scheduler {
 			e.1.tick();
 		}
 	}
-	pub unsafe fn draw<'a, 'b: 'a>(&self, ctx: &'a GameContext<'b>, canvas: &mut Canvas<Window>) {
-		self.drawNonGlobal(ctx, canvas);
-		self.drawGlobal(ctx, canvas);
+	/// Draws the screen and global entities whose layer is in `layers`, lowest layer first.
+	/// `alpha` is passed on to `drawInterpolated` when given.
+	pub unsafe fn drawLayers<'a, 'b: 'a, R: RangeBounds<i32>>(&self, ctx: &'a GameContext<'b>, canvas: &mut dyn Renderer, alpha: Option<f32>, layers: R) {
+		Self::drawSorted(ctx, ctx.activeScreenEntityIter().chain(ctx.globalEntityIter()), canvas, alpha, layers);
 	}
-	pub unsafe fn drawGlobal(&self, ctx: &GameContext, canvas: &mut Canvas<Window>) {
-		for id in ctx.globalEntityIter() {
-			(&mut *ctx.getHolder().getEntityDyn(id).unwrap()).draw(canvas);
-		}
+	pub unsafe fn drawGlobal(&self, ctx: &GameContext, canvas: &mut dyn Renderer) {
+		Self::drawSorted(ctx, ctx.globalEntityIter(), canvas, None, ..);
+	}
+	pub unsafe fn drawNonGlobal(&self, ctx: &GameContext, canvas: &mut dyn Renderer) {
+		Self::drawSorted(ctx, ctx.activeScreenEntityIter(), canvas, None, ..);
 	}
-	pub unsafe fn drawNonGlobal(&self, ctx: &GameContext, canvas: &mut Canvas<Window>) {
-		for entity in ctx.activeScreenEntityIter().map(|id| &mut *ctx.getHolder().getEntityDyn(id).unwrap()).filter(|e| e.drawPriority() == 0) {
-			entity.draw(canvas);
+	/// Within a layer, entities without a depth come first, then the rest from back to front.
+	/// Ties fall back to the ID so the order is the same every frame.
+	unsafe fn drawSorted<I: Iterator<Item=ID>, R: RangeBounds<i32>>(ctx: &GameContext, ids: I, canvas: &mut dyn Renderer, alpha: Option<f32>, layers: R) {
+		let mut entities: Vec<_> = ids.map(|id| (id, &*ctx.getHolder().getEntityDyn(id).unwrap()))
+			.filter(|(_, e)| layers.contains(&e.drawLayer()))
+			.collect();
+		entities.sort_by_key(|&(id, e)| (e.drawLayer(), e.drawDepth(), id));
+		for (_, entity) in entities {
+			Self::drawEntity(entity, canvas, alpha);
 		}
-		for entity in ctx.activeScreenEntityIter().map(|id| &mut *ctx.getHolder().getEntityDyn(id).unwrap()).filter(|e| e.drawPriority() == 1) {
-			entity.draw(canvas);
+	}
+	fn drawEntity(entity: &dyn EntityDyn, canvas: &mut dyn Renderer, alpha: Option<f32>) {
+		match alpha {
+			Some(alpha) => entity.drawInterpolated(canvas, alpha),
+			None => entity.draw(canvas),
 		}
 	}
 }
@@ -0,0 +1,137 @@
+//! Level scripts, available with the `scripting` feature.
+//!
+//! A level names its script in the campaign manifest. The script is run when the level loads and
+//! again whenever the level is put back to an earlier state, such as after dying, restarting the
+//! room or loading a save, so its globals always start over with the level. It may define these
+//! global functions, all optional:
+//!
+//! - `onStart()` is called before the first step.
+//! - `onStep()` is called after every simulation step.
+//!
+//! A `Script` tile calls the global function it names as `handler(event, x, y)`, where `event` is
+//! `"enter"`, `"stay"` or `"exit"` as the player walks over it, or `"sword"` when a swing hits it.
+//! `x` and `y` are the tile's coordinates. Handlers run before `onStep`.
+//!
+//! While a hook runs, the table `game` offers:
+//!
+//! - `game.spawnTile(x, y, tile, collision)` places a tile on the active screen. `collision` is one of
+//!   `"None"`, `"Block"`, `"Burn"`, `"Key"`, `"KeyBlock"`, `"Abyss"`, `"SnakeKill"`, `"SwitchImmune"`,
+//!   `"Health"`, `"CannonSword"`, `"Win"` or `"Checkpoint"`, and defaults to `"None"`.
+//! - `game.spawnTiles(x, y, endX, endY, tile, collision)` fills a rectangle of tiles the same way.
+//! - `game.sendCounter(entity, value)` sends a counter message, e.g. `i32::MIN` triggers a generator.
+//! - `game.damage(entity, amount)` sends a damage message.
+//! - `game.activateEntity(entity, global)` places an inactive entity into the game.
+//! - `game.playerPosition()` returns the x and y of the centre of the player.
+//! - `game.publish(topic, value)` publishes an event, either a built in topic such as `"KeyPickedUp"`
+//!   or any other name.
+//! - `game.playSound(name)` plays a sound effect such as `"KeyPickup"`.
+//!
+//! Entities are referred to by the number the editor shows for them. Tiles and entities change
+//! at the end of the step, like everything else done through `PO`.
+//!
+//! Scripts are loaded from source every time. The older precompiled `LuaFunction` helpers in
+//! `ScriptingUtils.rs` are not built and are not used here.
+
+use rlua::{Lua, Function, ToLuaMulti, Error};
+
+use std::fs;
+
+use crate::{ID, Vector};
+use crate::EventProcessor::{PO, Envelope, CollisionMsg, CounterMsg, Topic};
+use crate::MapMod::{Tile, CollisionType};
+use crate::SoundMod::{Sound, SOUNDS};
+
+pub struct Scripts {
+	lua: Lua,
+}
+
+impl Scripts {
+	pub fn load(filename: &str) -> rlua::Result<Scripts> {
+		let lua = Lua::new();
+		let source = fs::read_to_string(filename).map_err(Error::external)?;
+		lua.context(|context| context.load(&source).set_name(filename)?.exec())?;
+		Ok(Scripts {lua})
+	}
+	/// Calls the global function `name` with `args` if the script defines it.
+	pub fn call<A>(&self, po: &PO, name: &str, args: A) -> rlua::Result<()> where
+	A: for<'lua> ToLuaMulti<'lua> {
+		self.lua.context(|context| {
+			let function = match context.globals().get::<_, Option<Function>>(name)? {
+				Some(function) => function,
+				None => return Ok(()),
+			};
+			context.scope(|scope| {
+				let game = context.create_table()?;
+				game.set("spawnTile", scope.create_function(|_, (x, y, tile, collision): (u16, u16, u16, Option<String>)| {
+					po.spawnTile(Tile::new(tile, collisionFromName(collision)?), (x, y));
+					Ok(())
+				})?)?;
+				game.set("spawnTiles", scope.create_function(|_, (x, y, endX, endY, tile, collision): (u16, u16, u16, u16, u16, Option<String>)| {
+					po.spawnTiles(Tile::new(tile, collisionFromName(collision)?), (x, y), (endX, endY));
+					Ok(())
+				})?)?;
+				game.set("sendCounter", scope.create_function(|_, (entity, value): (u64, i32)| {
+					Ok(po.sendCounterMsg(Envelope::new(CounterMsg(value), ID::new(entity, 0), ID::empty())))
+				})?)?;
+				game.set("damage", scope.create_function(|_, (entity, amount): (u64, i32)| {
+					Ok(po.sendCollisionMsg(Envelope::new(CollisionMsg::Damage(amount), ID::new(entity, 0), ID::empty())))
+				})?)?;
+				game.set("activateEntity", scope.create_function(|_, (entity, global): (u64, Option<bool>)| {
+					po.activateEntity(ID::new(entity, 0), global.unwrap_or(false));
+					Ok(())
+				})?)?;
+				game.set("playerPosition", scope.create_function(|_, ()| {
+					let Vector(x, y) = po.getCtx().getPlayer().getCenter();
+					Ok((x, y))
+				})?)?;
+				game.set("publish", scope.create_function(|_, (topic, value): (String, Option<i32>)| {
+					po.publish(topicFromName(topic), value.unwrap_or(0), ID::empty());
+					Ok(())
+				})?)?;
+				game.set("playSound", scope.create_function(|_, name: String| {
+					po.playSound(soundFromName(&name)?);
+					Ok(())
+				})?)?;
+				context.globals().set("game", game)?;
+				let result = function.call::<_, ()>(args);
+				context.globals().set("game", rlua::Nil)?;
+				result
+			})
+		})
+	}
+}
+
+fn collisionFromName(name: Option<String>) -> rlua::Result<CollisionType> {
+	Ok(match name.as_deref().unwrap_or("None") {
+		"None" => CollisionType::None,
+		"Block" => CollisionType::Block,
+		"Burn" => CollisionType::Burn,
+		"Key" => CollisionType::Key,
+		"KeyBlock" => CollisionType::KeyBlock,
+		"Abyss" => CollisionType::Abyss,
+		"SnakeKill" => CollisionType::SnakeKill,
+		"SwitchImmune" => CollisionType::SwitchImmune,
+		"Health" => CollisionType::Health,
+		"CannonSword" => CollisionType::CannonSword,
+		"Win" => CollisionType::Win,
+		"Checkpoint" => CollisionType::Checkpoint,
+		other => return Err(Error::RuntimeError(format!("Unknown collision type \"{}\"", other))),
+	})
+}
+
+fn topicFromName(name: String) -> Topic {
+	match name.as_str() {
+		"SwitchToggled" => Topic::SwitchToggled,
+		"KeyPickedUp" => Topic::KeyPickedUp,
+		"ScreenEntered" => Topic::ScreenEntered,
+		"BossActivated" => Topic::BossActivated,
+		"BossDied" => Topic::BossDied,
+		_ => Topic::Named(name),
+	}
+}
+
+fn soundFromName(name: &str) -> rlua::Result<Sound> {
+	SOUNDS.iter().copied().find(|sound| format!("{:?}", sound) == name)
+		.ok_or_else(|| Error::RuntimeError(format!("Unknown sound \"{}\"", name)))
+}
+
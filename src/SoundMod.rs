@@ -0,0 +1,136 @@
+use sdl2::mixer::{self, Channel, Chunk, InitFlag, Sdl2MixerContext};
+
+use serde::{Serialize, Deserialize};
+
+use std::collections::HashMap;
+
+const SOUND_DIR: &str = "Resources/Sounds";
+const MUSIC_CHANNELS: [i32; 2] = [0, 1];
+const MIXING_CHANNELS: i32 = 16;
+/// Length in milliseconds of the crossfade between two levels' music.
+pub const CROSSFADE_MS: i32 = 1500;
+
+/// Sound effects gameplay code can ask for with `PO::playSound`. Each is read from a WAV file named
+/// after it in `Resources/Sounds`, e.g. `Resources/Sounds/SwordSwing.wav`. Effects without a file are silent.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Sound {
+	SwordSwing,
+	Hit,
+	KeyPickup,
+	GateSpawn,
+	CannonFire,
+	BossDeath,
+}
+
+pub const SOUNDS: &'static [Sound] = &[
+	Sound::SwordSwing,
+	Sound::Hit,
+	Sound::KeyPickup,
+	Sound::GateSpawn,
+	Sound::CannonFire,
+	Sound::BossDeath,
+];
+
+impl Sound {
+	pub fn getFilename(&self) -> String {
+		format!("{}/{:?}.wav", SOUND_DIR, self)
+	}
+}
+
+/// Plays effects and the background music. A disabled manager accepts every call and does nothing,
+/// so headless runs and machines without an audio device need no special casing.
+pub struct SoundManager {
+	_context: Option<Sdl2MixerContext>,
+	enabled: bool,
+	muted: bool,
+	sounds: HashMap<Sound, Chunk>,
+	/// Music is played as chunks on two reserved channels so one can fade out while the other fades in.
+	music: [Option<(String, Chunk)>; 2],
+	current: usize,
+}
+
+impl SoundManager {
+	pub fn new() -> SoundManager {
+		if let Err(e) = mixer::open_audio(44100, mixer::DEFAULT_FORMAT, 2, 1024) {
+			eprintln!("Warning: Audio could not be opened: {}", e);
+			return SoundManager::disabled();
+		}
+		let context = mixer::init(InitFlag::OGG).map_err(|e| eprintln!("Warning: Ogg support could not be loaded: {}", e)).ok();
+		mixer::allocate_channels(MIXING_CHANNELS);
+		mixer::reserve_channels(MUSIC_CHANNELS.len() as i32);
+		let mut sounds = HashMap::new();
+		let mut missing = vec![];
+		for &sound in SOUNDS {
+			match Chunk::from_file(sound.getFilename()) {
+				Ok(chunk) => {sounds.insert(sound, chunk);},
+				Err(e) => missing.push((sound, e)),
+			}
+		}
+		// One line for all of them, the files are optional
+		if let Some((_, e)) = missing.first() {
+			let names: Vec<String> = missing.iter().map(|(sound, _)| format!("{:?}", sound)).collect();
+			eprintln!("Warning: No sound for {} in \"{}\" ({})", names.join(", "), SOUND_DIR, e);
+		}
+		SoundManager {_context: context, enabled: true, muted: false, sounds, music: [None, None], current: 0}
+	}
+	pub fn disabled() -> SoundManager {
+		SoundManager {_context: None, enabled: false, muted: false, sounds: HashMap::new(), music: [None, None], current: 0}
+	}
+	pub fn isEnabled(&self) -> bool {
+		self.enabled
+	}
+	pub fn isMuted(&self) -> bool {
+		self.muted
+	}
+	/// Silences effects and music without stopping them.
+	pub fn setMuted(&mut self, muted: bool) {
+		self.muted = muted;
+		if self.enabled {
+			Channel::all().set_volume(if muted {0} else {mixer::MAX_VOLUME});
+		}
+	}
+	pub fn play(&self, sound: Sound) {
+		if let Some(chunk) = self.sounds.get(&sound) {
+			// Every free channel being busy just drops the effect
+			let _ = Channel::all().play(chunk, 0);
+		}
+	}
+	/// Crossfades to `filename`, or fades out if it is `None`. Asking for the music already playing keeps it going,
+	/// as does music that fails to load.
+	pub fn playMusic(&mut self, filename: Option<&str>) {
+		if !self.enabled {return;}
+		if self.music[self.current].as_ref().map(|(name, _)| name.as_str()) == filename {return;}
+		let chunk = match filename.map(Chunk::from_file) {
+			Some(Ok(chunk)) => Some(chunk),
+			Some(Err(e)) => {
+				eprintln!("Warning: Could not play \"{}\": {}", filename.unwrap(), e);
+				return;
+			},
+			None => None,
+		};
+		let next = 1 - self.current;
+		Channel(MUSIC_CHANNELS[next]).halt();
+		self.music[next] = None;
+		if self.music[self.current].is_some() {
+			Channel(MUSIC_CHANNELS[self.current]).fade_out(CROSSFADE_MS);
+		}
+		self.current = next;
+		if let (Some(filename), Some(chunk)) = (filename, chunk) {
+			match Channel(MUSIC_CHANNELS[next]).fade_in(&chunk, -1, CROSSFADE_MS) {
+				Ok(_) => self.music[next] = Some((filename.to_string(), chunk)),
+				Err(e) => eprintln!("Warning: Could not play \"{}\": {}", filename, e),
+			}
+		}
+	}
+}
+
+impl Drop for SoundManager {
+	fn drop(&mut self) {
+		if self.enabled {
+			Channel::all().halt();
+			self.sounds.clear();
+			self.music = [None, None];
+			mixer::close_audio();
+		}
+	}
+}
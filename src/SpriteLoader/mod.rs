@@ -1,25 +1,26 @@
 extern crate sdl2;
 
 use sdl2::rect::{Rect, Point};
-use sdl2::render::{Canvas, Texture, TextureCreator};
+use sdl2::render::{Texture, TextureCreator};
 use sdl2::image::LoadTexture;
-use sdl2::video::{WindowContext, Window};
 
 use std::ops::Deref;
 use std::io::{self, ErrorKind, Error};
 use std::fs;
 use std::fmt::{Debug, Formatter};
 
+use crate::Rendering::Renderer;
+
 const MIRROR_PATTERN: &'static str = "__half";
 
 mod Parsing;
 
 use Parsing::*;
 
-fn loadSprites<'a, 'b> (creator: &'a TextureCreator<WindowContext>, filenames: &'b [&'b str]) -> Result<Vec<Sprite<'a>>, String> {
+fn loadSprites<'a, 'b, C>(creator: &'a TextureCreator<C>, filenames: &'b [&'b str]) -> Result<Vec<Sprite<'a>>, String> {
     let mut sprites = vec![];
     for filename in filenames {
-        sprites.push(Sprite(creator.load_texture(filename)?, filename.contains(MIRROR_PATTERN)));
+        sprites.push(Sprite(creator.load_texture(filename)?, filename.contains(MIRROR_PATTERN), filename.to_string()));
     }
     Ok(sprites)
 }
@@ -30,7 +31,7 @@ pub struct Sprites<'a> {
 }
 
 impl<'a> Sprites<'a> {
-    pub fn new<'b> (creator: &'a TextureCreator<WindowContext>, filenames: &'b [&'b str]) -> io::Result<Sprites<'a>> {
+    pub fn new<'b, C>(creator: &'a TextureCreator<C>, filenames: &'b [&'b str]) -> io::Result<Sprites<'a>> {
         match loadSprites(creator, filenames) {
 			Ok(sprites) => Ok(Sprites {
 		        sprites,
@@ -52,7 +53,7 @@ pub struct Animations<'a> {
 }
 
 impl<'a> Animations<'a> {
-    pub fn new(filename: &str, animationPaths: &[&str], creator: &'a TextureCreator <WindowContext>) -> io::Result<Animations<'a>> {
+    pub fn new<C>(filename: &str, animationPaths: &[&str], creator: &'a TextureCreator<C>) -> io::Result<Animations<'a>> {
 		let file = fs::read_to_string(filename)?;
 		let animationPaths = parse(&file, animationPaths)?;
 
@@ -79,7 +80,7 @@ impl<'a> Animations<'a> {
         &self.animations[self.activeAnimation]
     }
 
-    pub fn drawNextFrame(&self, canvas: &mut Canvas<Window>, position: Rect) {
+    pub fn drawNextFrame(&self, canvas: &mut dyn Renderer, position: Rect) {
         match self.getAnimation() {
 
             Animation::Standard(animation) => 
@@ -171,7 +172,7 @@ impl<'a> StandardAnimation<'a> {
         
         Ok(Animation{sprites, frames,})
     }*/
-    pub fn fromFiles<'b> (creator: &'a TextureCreator<WindowContext>, filenames: &'b [&'b str], positions: &'b [usize]) -> io::Result<StandardAnimation<'a>> {
+    pub fn fromFiles<'b, C>(creator: &'a TextureCreator<C>, filenames: &'b [&'b str], positions: &'b [usize]) -> io::Result<StandardAnimation<'a>> {
         let length = filenames.len();
         
         let mut frames = vec![];
@@ -217,7 +218,7 @@ impl<'a> Deref for StandardAnimation<'a> {
 
 pub type Mirror = bool;
 
-pub struct Sprite<'a> (Texture<'a>, Mirror);
+pub struct Sprite<'a> (Texture<'a>, Mirror, String);
 
 impl<'a> Debug for Sprite<'a> {
 	fn fmt(&self, formatter: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
@@ -226,21 +227,25 @@ impl<'a> Debug for Sprite<'a> {
 }
 
 impl<'a> Sprite<'a> {
-	#[allow(unused_must_use)]
-	pub fn draw(&self, canvas: &mut Canvas<Window>, quad: Rect, flipHorizontal: bool, flipVertical: bool) {
+	pub fn draw(&self, canvas: &mut dyn Renderer, quad: Rect, flipHorizontal: bool, flipVertical: bool) {
         if self.1 {
             let mut quad = quad;
             quad.w = quad.w / 2;
-            canvas.copy_ex(&self.0, None, quad, 0f64, None, true, flipVertical); //Render the left half
+            canvas.drawSprite(self, quad, 0f64, None, true, flipVertical); //Render the left half
             quad.x += quad.w;
-            canvas.copy_ex(&self.0, None, quad, 0f64, None, false, flipVertical); //Render the right half
+            canvas.drawSprite(self, quad, 0f64, None, false, flipVertical); //Render the right half
         }
         else {
-            canvas.copy_ex(&self.0, None, quad, 0f64, None, flipHorizontal, flipVertical);
+            canvas.drawSprite(self, quad, 0f64, None, flipHorizontal, flipVertical);
         }
     }
-	pub fn drawRot(&self, canvas: &mut Canvas<Window>, quad: Rect, rot: f64, center: Point) {
-		canvas.copy_ex(&self.0, None, quad, rot, Some(center), false, false);
+	pub fn drawRot(&self, canvas: &mut dyn Renderer, quad: Rect, rot: f64, center: Point) {
+		canvas.drawSprite(self, quad, rot, Some(center), false, false);
+	}
+	pub fn getTexture(&self) -> &Texture<'a> {
+		&self.0
+	}
+	pub fn getName(&self) -> &str {
+		&self.2
 	}
 }
-
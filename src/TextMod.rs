@@ -0,0 +1,44 @@
+use sdl2::render::{Texture, TextureCreator};
+use sdl2::video::WindowContext;
+use sdl2::ttf::Font;
+use sdl2::pixels::Color;
+
+use std::collections::HashMap;
+
+/// Text, color and wrap width of a rendered line
+type Key = (String, Color, Option<u32>);
+
+/// A font and the textures of the text drawn with it. Text is only rendered again once it changes,
+/// textures not asked for between two calls to `endFrame` are dropped.
+pub struct TextCache<'tex, 'ttf> {
+	font: Font<'ttf, 'static>,
+	creator: &'tex TextureCreator<WindowContext>,
+	textures: HashMap<Key, (Texture<'tex>, bool)>,
+}
+
+impl<'tex, 'ttf> TextCache<'tex, 'ttf> {
+	pub fn new(font: Font<'ttf, 'static>, creator: &'tex TextureCreator<WindowContext>) -> TextCache<'tex, 'ttf> {
+		TextCache {font, creator, textures: HashMap::new()}
+	}
+	/// `None` for empty text or if rendering failed. Text wider than `wrap` pixels is broken into lines.
+	pub fn get(&mut self, text: &str, color: Color, wrap: Option<u32>) -> Option<&Texture<'tex>> {
+		if text.is_empty() {return None;}
+		let key = (text.to_string(), color, wrap);
+		if !self.textures.contains_key(&key) {
+			let rendered = self.font.render(text);
+			let surface = match wrap {
+				Some(width) => rendered.blended_wrapped(color, width),
+				None => rendered.blended(color),
+			}.ok()?;
+			let texture = surface.as_texture(self.creator).ok()?;
+			self.textures.insert(key.clone(), (texture, true));
+		}
+		let entry = self.textures.get_mut(&key)?;
+		entry.1 = true;
+		Some(&entry.0)
+	}
+	/// Drops the textures not used since the last call.
+	pub fn endFrame(&mut self) {
+		self.textures.retain(|_, (_, used)| std::mem::take(used));
+	}
+}
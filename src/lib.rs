@@ -5,9 +5,12 @@ extern crate serde_json;
 extern crate rand;
 
 use sdl2::{Sdl, VideoSubsystem, EventPump};
-use sdl2::render::{Canvas, TextureCreator};
+use sdl2::render::{Canvas, TextureCreator, BlendMode};
 use sdl2::video::{WindowContext, Window};
+use sdl2::ttf::Sdl2TtfContext;
 use sdl2::event::Event;
+use sdl2::keyboard::Scancode;
+use sdl2::controller::Button;
 use sdl2::hint;
 use sdl2::pixels::Color;
 use sdl2::rect::{Point, Rect};
@@ -20,6 +23,7 @@ use serde_json::Deserializer;
 use std::io;
 use std::fs::File;
 use std::cell::UnsafeCell;
+use std::time::{Duration, Instant};
 
 mod PlayerMod;
 mod SpriteLoader;
@@ -32,22 +36,63 @@ mod Scheduling;
 mod GameContextMod;
 pub mod Entities;
 mod EditorContextMod;
+mod HeadlessMod;
+mod Rendering;
+mod RngMod;
+mod ReplayMod;
+mod SaveSlotMod;
+mod CampaignMod;
+mod CameraMod;
+mod SoundMod;
+mod DialogueMod;
+mod HudMod;
+mod GameStateMod;
+mod ControlsMod;
+mod ControllerMod;
+mod TextMod;
+#[cfg(feature = "scripting")]
+mod ScriptingMod;
 
 pub use VectorMod::Vector;
 pub use Vec2dMod::Vec2d;
-pub use PlayerMod::Player;
+pub use PlayerMod::{Player, Signals, Mapping, Action};
 
 pub use MapMod::*;
 pub use GameContextMod::*;
 pub use EditorContextMod::{EditorContext, EditorContextDeps, createText};
+pub use HeadlessMod::Headless;
+pub use Rendering::{Renderer, OffsetRenderer, DrawRecorder, DrawCall};
+pub use SpriteLoader::Sprite;
+pub use RngMod::GameRng;
+pub use ReplayMod::{Replay, ReplayRecorder, ReplayPlayer};
+pub use CampaignMod::{Campaign, LevelEntry, DEFAULT_TILESET};
+pub use CameraMod::Camera;
+pub use SoundMod::{Sound, SoundManager};
+pub use DialogueMod::Dialogue;
+pub use HudMod::Hud;
+pub use GameStateMod::{GameState, PauseMenu, PauseItem};
+pub use ControlsMod::Rebinding;
+pub use ControllerMod::Controllers;
+pub use TextMod::TextCache;
+#[cfg(feature = "scripting")]
+pub use ScriptingMod::Scripts;
+pub use SaveSlotMod::{SlotInfo, SlotSelect, SLOT_COUNT, slotSaveFile, hasSaveFile, clearSaveFile};
 
 use PlayerMod::SignalsBuilder;
 
-pub use EventProcessor::PO;
+pub use EventProcessor::{PO, Topic};
 
-use Scheduling::Scheduler;
+use Scheduling::{Scheduler, OVERLAY_LAYER};
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+/// Length of one simulation step. Every timer in the game counts these steps.
+pub const TIMESTEP: Duration = Duration::from_nanos(1_000_000_000 / 60);
+/// Length of the slide between screens in simulation steps.
+pub const DEFAULT_TRANSITION_STEPS: u32 = 30;
+
+/// Upper bound on simulation steps per rendered frame so a long stall cannot snowball.
+const MAX_STEPS_PER_FRAME: u32 = 5;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
 pub struct ID(u64);
 
 const ID_MASK: u64 = 0xffffffffffffff00;
@@ -76,20 +121,65 @@ impl ID {
 	}
 }
 
-pub struct GameManager {
+/// `'tex` is the texture creator the HUD and text are drawn with, `'ttf` the TTF context of the font.
+pub struct GameManager<'tex, 'ttf> {
 	sdlContext: Sdl,
 	videoSubsystem: VideoSubsystem,
 	canvas: Canvas<Window>,
 	events: EventPump,
-	screenPos: Point,
+	camera: Camera,
 	scheduler: Scheduler,
 	quit: bool,
 	pub advance: bool,
+	lastFrame: Instant,
+	accumulator: Duration,
+	pendingSignals: Signals,
+	interpolate: bool,
+	recorder: Option<ReplayRecorder>,
+	playback: Option<ReplayPlayer>,
+	saveRequest: Option<SaveRequest>,
+	steps: u64,
+	slide: Option<Slide>,
+	transitionSteps: u32,
+	sound: SoundManager,
+	#[cfg(feature = "scripting")]
+	scripts: Option<Scripts>,
+	dialogue: Option<Dialogue>,
+	text: Option<TextCache<'tex, 'ttf>>,
+	hud: Option<Hud<'tex>>,
+	state: GameState,
+	pauseMenu: PauseMenu,
+	/// The level as it was when the player entered the current room or reached a checkpoint in it.
+	/// Restored by `SaveRequest::RestartRoom` and after dying.
+	roomSnapshot: Option<String>,
+	mapping: Mapping,
+	/// Where rebound keys are saved
+	keyConfig: Option<String>,
+	rebinding: Option<Rebinding>,
+	controllers: Controllers,
+}
+
+/// A screen transition in progress. Gameplay is paused until it finishes.
+#[derive(Copy, Clone)]
+struct Slide {
+	from: usize,
+	fromCamera: Point,
+	direction: Direction,
+	step: u32,
+}
+
+/// Asked for by the player with F5 (save), F9 (load) or from the pause menu. `mainLoop` stops so the caller can act on it.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SaveRequest {
+	Save,
+	Load,
+	/// Put the level back the way it was on entering the room, see `getRoomSnapshot`
+	RestartRoom,
 }
 
-impl GameManager {
+impl<'tex, 'ttf> GameManager<'tex, 'ttf> {
 	
-	pub fn initialize(name: &'static str, width: u32, height: u32, color: Color) -> (GameManager, TextureCreator<WindowContext>) {
+	pub fn initialize(name: &'static str, width: u32, height: u32, color: Color) -> (GameManager<'tex, 'ttf>, TextureCreator<WindowContext>) {
 		
 		let sdlContext = sdl2::init().unwrap();
 		let videoSubsystem = sdlContext.video().unwrap();
@@ -117,9 +207,42 @@ impl GameManager {
 		canvas.set_draw_color(color);
 
 		let quit = false;
-		let screenPos = Point::new(0, 0);
+		let camera = Camera::new(width, height);
+		let controllers = Controllers::new(&sdlContext);
 
-		(GameManager {sdlContext, videoSubsystem, canvas, events, screenPos, quit, scheduler: Scheduler::new(), advance: false}, textureCreator,) 
+		(GameManager {
+			sdlContext,
+			videoSubsystem,
+			canvas,
+			events,
+			camera,
+			quit,
+			scheduler: Scheduler::new(),
+			advance: false,
+			lastFrame: Instant::now(),
+			accumulator: Duration::ZERO,
+			pendingSignals: Signals::default(),
+			interpolate: false,
+			recorder: None,
+			playback: None,
+			saveRequest: None,
+			steps: 0,
+			slide: None,
+			transitionSteps: DEFAULT_TRANSITION_STEPS,
+			sound: SoundManager::disabled(),
+			#[cfg(feature = "scripting")]
+			scripts: None,
+			dialogue: None,
+			text: None,
+			hud: None,
+			state: GameState::Playing,
+			pauseMenu: PauseMenu::new(),
+			roomSnapshot: None,
+			mapping: Mapping::default(),
+			keyConfig: None,
+			rebinding: None,
+			controllers,
+		}, textureCreator,) 
 	}
 	
 	#[inline(always)]
@@ -127,71 +250,429 @@ impl GameManager {
 		
 		self.canvas.clear();
 		
-		let mut signals = SignalsBuilder::default();
+		let events: Vec<Event> = self.events.poll_iter().collect();
+		for event in events.iter() {
+			self.quit |= Self::windowEvents(event);
+			self.controllers.handleEvent(event);
+			if self.state == GameState::Paused {
+				// Controllers drive the menu like the keyboard, except when waiting for a key to bind
+				let menuEvent = Controllers::menuEvent(event).filter(|_| !self.rebinding.as_ref().map_or(false, Rebinding::isWaiting));
+				let event = menuEvent.as_ref().unwrap_or(event);
+				if let Some(ref mut rebinding) = self.rebinding {
+					let changed = rebinding.handleEvent(event, &mut self.mapping);
+					if rebinding.isDone() {self.rebinding = None;}
+					if changed {self.saveKeyBindings();}
+				}
+				else if let Some(item) = self.pauseMenu.handleEvent(event) {
+					self.pauseItem(item);
+				}
+				continue;
+			}
+			match event {
+				Event::KeyDown{scancode: Some(Scancode::F5), repeat: false, ..} => self.saveRequest = Some(SaveRequest::Save),
+				Event::KeyDown{scancode: Some(Scancode::F9), repeat: false, ..} => self.saveRequest = Some(SaveRequest::Load),
+				Event::KeyDown{scancode: Some(Scancode::Escape), repeat: false, ..}
+				| Event::ControllerButtonDown{button: Button::Start, ..} => {
+					self.pauseMenu = PauseMenu::new();
+					self.state = GameState::Paused;
+				},
+				_ => (),
+			}
+		}
 
-		for event in self.events.poll_iter() {
-			self.quit = Self::windowEvents(&event);
-			signals.addEvent(&event);
+		// Events while paused still count so keys let go in the menu are seen as released afterwards
+		let mut signals = SignalsBuilder::new(&self.mapping);
+		for event in events.iter() {
+			signals.addEvent(event);
 		}
-		
-		let ctx = unsafe {po.get_mut().getCtxMut()};
-		let player = ctx.holder.getMutTyped(ctx.getPlayerID()).unwrap();
-		player.signal(signals.build(&self.events));
-//		player.transition(&mut po.getCtx()map);
-//		po.get_mut().transition();
+		// Input is held until the next simulation step so presses between steps are not lost
+		self.pendingSignals = self.pendingSignals.merge(signals.build(&self.events, self.controllers.get()));
+
+		let now = Instant::now();
+		// Time spent paused is not made up afterwards
+		if self.state == GameState::Playing {
+			self.accumulator = (self.accumulator + (now - self.lastFrame)).min(TIMESTEP * MAX_STEPS_PER_FRAME);
+		}
+		self.lastFrame = now;
 
 		unsafe {
-			if (&mut *po.get()).getCtxMut().getPlayerMut().transition((&mut *po.get()).getCtxMut()) {
-				//println!("dgf");
-				//po.get_mut().getCtxMut().resetCollisionLists();
-				po.get_mut().getCtxMut().disableEntityCollisionFrame();
+			if self.roomSnapshot.is_none() {
+				self.roomSnapshot = snapshotCtx(po.get_mut().getCtx());
+			}
+			while self.state == GameState::Playing && self.accumulator >= TIMESTEP && !self.quit {
+				self.accumulator -= TIMESTEP;
+				self.steps += 1;
+				if let Some(ref mut slide) = self.slide {
+					// Presses during the slide are dropped rather than acted on afterwards
+					self.pendingSignals = self.pendingSignals.releases();
+					slide.step += 1;
+					if slide.step >= self.transitionSteps {self.slide = None;}
+					continue;
+				}
+				let signals = match self.playback {
+					// The caller ends the replay once the recording has run out
+					Some(ref playback) if playback.isFinished() => {
+						self.quit = true;
+						self.advance = false;
+						break;
+					},
+					Some(ref mut playback) => playback.next(),
+					None => std::mem::take(&mut self.pendingSignals),
+				};
+				if let Some(ref mut recorder) = self.recorder {
+					recorder.record(signals);
+				}
+				// An open text box takes the input and holds the game until it is closed.
+				// Only a fresh press turns the page, not the key being held or repeating.
+				if let Some(ref mut dialogue) = self.dialogue {
+					if signals.attackPressed && !dialogue.advance() {self.dialogue = None;}
+					self.pendingSignals = signals.releases().merge(std::mem::take(&mut self.pendingSignals));
+					continue;
+				}
+				match updateFrame(&self.scheduler, po, signals) {
+					1 => {
+						self.quit = true;
+						self.advance = true;
+						self.state = GameState::LevelComplete;
+					},
+					2 => {
+						self.quit = true;
+						self.advance = false;
+						self.state = GameState::GameOver;
+					},
+					_ => (),
+				}
+				#[cfg(feature = "scripting")]
+				self.runTileEvents(po);
+				#[cfg(feature = "scripting")]
+				self.runScript(po, "onStep");
+				for &sound in po.get_mut().getSounds() {
+					self.sound.play(sound);
+				}
+				if po.get_mut().takeCheckpoint() {
+					self.roomSnapshot = snapshotCtx(po.get_mut().getCtx());
+				}
+				// Opened even without a font so the simulation, and with it replays, does not depend on one
+				if let Some(pages) = po.get_mut().takeDialogue() {
+					self.dialogue = Some(Dialogue::new(pages));
+				}
+				if let Some(direction) = po.get_mut().getCtxMut().takeTransition() {
+					self.roomSnapshot = snapshotCtx(po.get_mut().getCtx());
+					if self.transitionSteps > 0 {
+						let from = po.get_mut().getCtx().getMap().getLastActiveScreenId();
+						self.slide = Some(Slide {from, fromCamera: self.camera.getPosition(), direction, step: 0});
+					}
+				}
+			}
+
+			let ctx = po.get_mut().getCtx();
+			let target: (i32, i32) = ctx.getPlayer().getCenter().into();
+			self.camera.update(target.into(), ctx.getMap().getActiveScreenId(), ctx.getMap().getMaxScreenCoords());
+
+			let alpha = if self.interpolate {Some(self.accumulator.as_secs_f32() / TIMESTEP.as_secs_f32())} else {None};
+			match self.slide {
+				Some(slide) => self.drawSlide(slide, po),
+				None => drawFrame(&self.scheduler, po, &mut self.canvas, self.camera.getPosition(), alpha),
 			}
 		}
+		if let Some(ref hud) = self.hud {
+			hud.draw(po.get_mut().getCtx(), &mut self.canvas, self.text.as_mut());
+		}
+		if let (Some(dialogue), Some(text)) = (&self.dialogue, &mut self.text) {
+			dialogue.draw(&mut self.canvas, text);
+		}
+		if self.state == GameState::Paused {
+			self.drawPauseMenu();
+		}
+		
+		self.present();
 		
+		!self.quit && self.saveRequest.is_none()
+	}
 
-		unsafe {
-			Scheduler::tick(po.get_mut().getCtxMut());
-			self.scheduler.execute(po, |id| {(&mut *(&*po.get()).getCtx().getHolder().getEntityDyn(id).unwrap()).getData(&*po.get(), EventProcessor::Key::new());});
-			po.get_mut().getCtxMut().resetCollisionLists();
-			self.scheduler.execute(po, |id| (&mut *(&*po.get()).getCtx().getHolder().getEntityDyn(id).unwrap()).update(&mut *po.get()) );
+	fn pauseItem(&mut self, item: PauseItem) {
+		match item {
+			PauseItem::Resume => self.state = GameState::Playing,
+			PauseItem::RestartRoom => {
+				self.state = GameState::Playing;
+				self.saveRequest = Some(SaveRequest::RestartRoom);
+			},
+			PauseItem::Options => self.pauseMenu.showOptions(true),
+			PauseItem::Quit => {
+				self.quit = true;
+				self.advance = false;
+			},
+			PauseItem::Interpolation => self.interpolate = !self.interpolate,
+			PauseItem::Sound => {
+				let muted = !self.sound.isMuted();
+				self.sound.setMuted(muted);
+			},
+			PauseItem::Controls => self.rebinding = Some(Rebinding::new()),
+			PauseItem::Back => self.pauseMenu.showOptions(false),
 		}
-		unsafe {
-			po.get_mut().getCtxMut().map.update();
+	}
 
-			match po.get_mut().doCommands() {
-				1 => {
-					self.quit = true;
-					self.advance = true;
-				},
-				2 => {
-					self.quit = true;
-					self.advance = false;
-				},
+	/// Dims the frozen game and lists the menu over it.
+	fn drawPauseMenu(&mut self) {
+		let (width, height) = self.canvas.output_size().unwrap_or((0, 0));
+		let (prevColor, prevBlend) = (self.canvas.draw_color(), self.canvas.blend_mode());
+		self.canvas.set_blend_mode(BlendMode::Blend);
+		self.canvas.set_draw_color(Color::RGBA(0, 0, 0, 0xa0));
+		let _ = self.canvas.fill_rect(Rect::new(0, 0, width, height));
+		self.canvas.set_draw_color(prevColor);
+		self.canvas.set_blend_mode(prevBlend);
+		let mut y = height as i32 / 3;
+		let (heading, lines) = match self.rebinding {
+			Some(ref rebinding) => ("Controls", rebinding.lines(&self.mapping)),
+			None => (GameState::Paused.getText().0, self.pauseMenu.lines(self.interpolate, !self.sound.isMuted())),
+		};
+		y += self.drawCentered(heading, y) + 30;
+		for (line, selected) in lines {
+			y += self.drawCentered(&if selected {format!("> {} <", line)} else {line}, y) + 15;
+		}
+	}
+
+	fn drawCentered(&mut self, text: &str, y: i32) -> i32 {
+		self.drawText(text, None, y)
+	}
+	/// Draws one line of text with its top at `y` and its left at `x`, or centered horizontally
+	/// without one, returning its height. Nothing is drawn without a font.
+	fn drawText(&mut self, text: &str, x: Option<i32>, y: i32) -> i32 {
+		let (width, _) = self.canvas.output_size().unwrap_or((0, 0));
+		let texture = match self.text.as_mut().and_then(|cache| cache.get(text, Color::WHITE, None)) {
+			Some(texture) => texture,
+			None => return 0,
+		};
+		let query = texture.query();
+		let x = x.unwrap_or((width as i32 - query.width as i32) / 2);
+		let _ = self.canvas.copy(texture, None, Rect::new(x, y, query.width, query.height));
+		query.height as i32
+	}
+
+	/// Shows the finished frame. Text that was not drawn in it is let go.
+	fn present(&mut self) {
+		self.canvas.present();
+		if let Some(ref mut text) = self.text {
+			text.endFrame();
+		}
+	}
+
+	/// Reads the key bindings from `filename` and saves any rebinding there. A missing file keeps the defaults.
+	pub fn loadKeyBindings(&mut self, filename: &str) {
+		match Mapping::load(filename) {
+			Ok(mapping) => self.mapping = mapping,
+			Err(e) if e.kind() == io::ErrorKind::NotFound => (),
+			Err(e) => eprintln!("Warning: Could not read key bindings from \"{}\": {}", filename, e),
+		}
+		self.keyConfig = Some(filename.to_string());
+	}
+	fn saveKeyBindings(&self) {
+		if let Some(ref filename) = self.keyConfig {
+			if let Err(e) = self.mapping.save(filename) {
+				eprintln!("Warning: Could not save key bindings to \"{}\": {}", filename, e);
+			}
+		}
+	}
+	pub fn getMapping(&self) -> &Mapping {
+		&self.mapping
+	}
+
+	pub fn getState(&self) -> GameState {
+		self.state
+	}
+	/// `mainLoop` only runs the game in `Playing`. The other screens are shown with `showScreen`.
+	pub fn setState(&mut self, state: GameState) {
+		self.state = state;
+		// Screens in between levels should not leave a backlog of steps behind
+		self.accumulator = Duration::ZERO;
+		self.lastFrame = Instant::now();
+	}
+	/// Whether the window was closed or the player chose to quit.
+	pub fn hasQuit(&self) -> bool {
+		self.quit
+	}
+
+	/// Shows the screen for the current state, with `image` above the text, until Return is pressed.
+	/// Escape quits.
+	pub fn showScreen(&mut self, image: Option<&Texture>) -> bool {
+		self.canvas.clear();
+		let mut next = false;
+		for event in self.events.poll_iter() {
+			self.quit |= Self::windowEvents(&event);
+			self.controllers.handleEvent(&event);
+			let start = matches!(event, Event::ControllerButtonDown{button: Button::Start, ..});
+			match Controllers::menuEvent(&event).unwrap_or(event) {
+				// Start goes on here rather than back as it does in the pause menu
+				_ if start => next = true,
+				Event::KeyDown{scancode: Some(Scancode::Return), repeat: false, ..} => next = true,
+				Event::KeyDown{scancode: Some(Scancode::Escape), repeat: false, ..} => self.quit = true,
 				_ => (),
 			}
+		}
+		let (_, height) = self.canvas.output_size().unwrap_or((0, 0));
+		let mut y = height as i32 / 3;
+		if let Some(image) = image {
+			let query = image.query();
+			let (width, _) = self.canvas.output_size().unwrap_or((0, 0));
+			let _ = self.canvas.copy(image, None, Rect::new((width as i32 - query.width as i32) / 2, y - query.height as i32 - 20, query.width, query.height));
+		}
+		let (heading, prompt) = self.state.getText();
+		y += self.drawCentered(heading, y) + 40;
+		self.drawCentered(prompt, y);
+
+		self.present();
+
+		!self.quit && !next
+	}
+
+	/// JSON of the level as it was when the player entered the current room or last reached a checkpoint.
+	pub fn getRoomSnapshot(&self) -> Option<&str> {
+		self.roomSnapshot.as_deref()
+	}
+	/// Forget the snapshot so the next `mainLoop` takes a new one, e.g. after loading a different level.
+	pub fn resetRoomSnapshot(&mut self) {
+		self.roomSnapshot = None;
+	}
+
+	/// Takes the pending save or load request. `mainLoop` can be resumed afterwards.
+	pub fn takeSaveRequest(&mut self) -> Option<SaveRequest> {
+		if self.quit {None} else {self.saveRequest.take()}
+	}
 
-			po.get_mut().getCtxMut().map.draw(&mut self.canvas, self.screenPos);
+	/// Blend entity positions between the last two simulation steps when drawing.
+	/// Smoother on displays faster than 60 Hz at the cost of drawing one step behind.
+	pub fn setInterpolation(&mut self, interpolate: bool) {
+		self.interpolate = interpolate;
+	}
+
+	/// Font for text drawn over the game, rendered with `creator`. Without one, sign text boxes still pause the game but are not drawn.
+	pub fn loadFont(&mut self, ttfContext: &'ttf Sdl2TtfContext, creator: &'tex TextureCreator<WindowContext>, filename: &str, size: u16) -> Result<(), String> {
+		self.text = Some(TextCache::new(ttfContext.load_font(filename, size)?, creator));
+		Ok(())
+	}
+
+	/// Loads the HUD's sprites. Nothing is drawn over the game until this is called.
+	pub fn loadHud(&mut self, creator: &'tex TextureCreator<WindowContext>) -> io::Result<()> {
+		self.hud = Some(Hud::new(creator)?);
+		Ok(())
+	}
+
+	/// Audio is off until a manager is set, e.g. `SoundManager::new()`.
+	pub fn setSoundManager(&mut self, sound: SoundManager) {
+		self.sound = sound;
+	}
+	pub fn getSoundManager(&mut self) -> &mut SoundManager {
+		&mut self.sound
+	}
+
+	/// Replaces the level script and runs its `onStart`.
+	#[cfg(feature = "scripting")]
+	pub fn setScripts<'a>(&mut self, scripts: Option<Scripts>, po: &mut UnsafeCell<PO<'a>>) {
+		self.scripts = scripts;
+		self.runScript(po, "onStart");
+	}
+	#[cfg(feature = "scripting")]
+	fn runScript<'a>(&self, po: &mut UnsafeCell<PO<'a>>, hook: &str) {
+		if let Some(ref scripts) = self.scripts {
+			if let Err(e) = scripts.call(po.get_mut(), hook, ()) {
+				eprintln!("Script error in {}: {}", hook, e);
+			}
 		}
-		unsafe { self.scheduler.draw(po.get_mut().getCtx(), &mut self.canvas) ;}
-		unsafe {po.get_mut().purge();}
-		
-		self.canvas.present();
-		
-		!self.quit
 	}
 
-	pub fn conglaturate(&mut self, conglaturate: &Texture) -> bool {
+	/// Calls the handler named by each script tile the player touched, as `handler(event, x, y)`.
+	#[cfg(feature = "scripting")]
+	fn runTileEvents<'a>(&self, po: &mut UnsafeCell<PO<'a>>) {
+		if let Some(ref scripts) = self.scripts {
+			let events = po.get_mut().getTileEvents().to_vec();
+			for (name, event, (x, y)) in events {
+				if let Err(e) = scripts.call(po.get_mut(), name.as_str(), (event.getName(), x, y)) {
+					eprintln!("Script error in {}: {}", name.as_str(), e);
+				}
+			}
+		}
+	}
+
+	/// Number of simulation steps a slide between screens takes. 0 switches screens instantly.
+	pub fn setTransitionSteps(&mut self, steps: u32) {
+		self.transitionSteps = steps;
+	}
+
+	/// The old screen scrolls out in the direction of travel while the new one scrolls in behind it.
+	unsafe fn drawSlide<'a>(&mut self, slide: Slide, po: &mut UnsafeCell<PO<'a>>) {
+		let progress = slide.step as f32 / self.transitionSteps as f32;
+		let (width, height) = self.camera.getSize();
+		let (dx, dy) = match slide.direction {
+			Direction::Up => (0, -(height as i32)),
+			Direction::Down => (0, height as i32),
+			Direction::Left => (-(width as i32), 0),
+			Direction::Right => (width as i32, 0),
+		};
+		let scrolled = Point::new((dx as f32 * progress) as i32, (dy as f32 * progress) as i32);
+		let remaining = Point::new(dx, dy) - scrolled;
+		po.get_mut().getCtxMut().map.drawScreen(slide.from, &mut self.canvas, slide.fromCamera + scrolled);
+		drawFrame(&self.scheduler, po, &mut self.canvas, self.camera.getPosition() - remaining, None);
+	}
+
+	/// Size in pixels of the area in the middle of the window the player can move in without scrolling.
+	pub fn setCameraDeadZone(&mut self, width: u32, height: u32) {
+		self.camera.setDeadZone(width, height);
+	}
+
+	/// Record the signals of every simulation step from now on.
+	pub fn startRecording(&mut self, recorder: ReplayRecorder) {
+		self.recorder = Some(recorder);
+	}
+	pub fn stopRecording(&mut self) -> Option<ReplayRecorder> {
+		self.recorder.take()
+	}
+	/// Feed the player from `playback` instead of the keyboard.
+	pub fn startPlayback(&mut self, playback: ReplayPlayer) {
+		self.playback = Some(playback);
+	}
+
+	/// Time spent in `mainLoop` since the last `resetPlayTime`, counted in simulation steps.
+	pub fn getPlayTime(&self) -> Duration {
+		TIMESTEP * self.steps as u32
+	}
+	pub fn resetPlayTime(&mut self) {
+		self.steps = 0;
+	}
+
+	/// Shows the save slots until one is picked. Needs the font from `loadFont`.
+	pub fn selectSlot(&mut self, select: &mut SlotSelect) -> Result<bool, String> {
+		if self.text.is_none() {
+			return Err(String::from("No font loaded"));
+		}
 		self.quit = false;
 		self.canvas.clear();
 		for event in self.events.poll_iter() {
-			self.quit = Self::windowEvents(&event);
+			self.quit |= Self::windowEvents(&event);
+			self.controllers.handleEvent(&event);
+			match Controllers::menuEvent(&event) {
+				Some(ref menuEvent) if !select.isNaming() => select.handleEvent(menuEvent),
+				_ => select.handleEvent(&event),
+			}
 		}
-		self.canvas.copy(conglaturate, None, Rect::new(0, 0, 250, 50));
-		
-		self.canvas.present();
-		
-		!self.quit
-		
+		let textInput = self.videoSubsystem.text_input();
+		if select.isNaming() {textInput.start();}
+		else {textInput.stop();}
+
+		let mut y = 50;
+		for (line, selected) in select.lines() {
+			y += self.drawText(&if selected {format!("> {}", line)} else {format!("  {}", line)}, Some(50), y) + 20;
+		}
+
+		self.present();
+
+		Ok(!self.quit && select.getChoice().is_none())
+	}
+
+	/// Clears the outcome of the last level so `mainLoop` can run the next one.
+	/// The room snapshot is kept so dying can restore it, see `resetRoomSnapshot`.
+	pub fn resume(&mut self) {
+		self.quit = false;
+		self.advance = false;
 	}
 
 	fn windowEvents(event: &Event) -> bool {
@@ -202,15 +683,75 @@ impl GameManager {
 	}
 }
 
-pub fn loadCtx<'a>(filename: &str, creator: &'a TextureCreator<WindowContext>) -> io::Result<GameContext<'a>> {
+/// Advances the game by exactly one frame using `signals` as the player input.
+/// Returns 1 if the level was won, 2 if the player died and 0 otherwise.
+unsafe fn updateFrame<'a>(scheduler: &Scheduler, po: &mut UnsafeCell<PO<'a>>, signals: Signals) -> u8 {
+	po.get_mut().clearSounds();
+	po.get_mut().clearTileEvents();
+	let ctx = po.get_mut().getCtxMut();
+	let player = ctx.holder.getMutTyped(ctx.getPlayerID()).unwrap();
+	player.signal(signals);
+//	player.transition(&mut po.getCtx()map);
+//	po.get_mut().transition();
+
+	if (&mut *po.get()).getCtxMut().getPlayerMut().transition((&mut *po.get()).getCtxMut()) {
+		//println!("dgf");
+		//po.get_mut().getCtxMut().resetCollisionLists();
+		po.get_mut().getCtxMut().disableEntityCollisionFrame();
+		let po = po.get_mut();
+		po.publish(Topic::ScreenEntered, po.getCtx().getMap().getActiveScreenId() as i32, po.getCtx().getPlayerID().getID());
+	}
+
+	Scheduler::tick(po.get_mut().getCtxMut());
+	scheduler.execute(po, |id| {(&mut *(&*po.get()).getCtx().getHolder().getEntityDyn(id).unwrap()).getData(&*po.get(), EventProcessor::Key::new());});
+	po.get_mut().deliverMessages();
+	scheduler.execute(po, |id| (&mut *(&*po.get()).getCtx().getHolder().getEntityDyn(id).unwrap()).update(&mut *po.get()) );
+
+	po.get_mut().deliverMessages();
+	po.get_mut().getCtxMut().map.update();
+
+	let result = po.get_mut().doCommands();
+	po.get_mut().purge();
+	result
+}
+
+unsafe fn drawFrame<'a>(scheduler: &Scheduler, po: &mut UnsafeCell<PO<'a>>, canvas: &mut dyn Renderer, screenPos: Point, alpha: Option<f32>) {
+	po.get_mut().getCtxMut().map.draw(canvas, screenPos);
+	scheduler.drawLayers(po.get_mut().getCtx(), &mut OffsetRenderer::new(canvas, screenPos), alpha, ..OVERLAY_LAYER);
+	po.get_mut().getCtxMut().map.drawOverlay(canvas, screenPos);
+	scheduler.drawLayers(po.get_mut().getCtx(), &mut OffsetRenderer::new(canvas, screenPos), alpha, OVERLAY_LAYER..);
+}
+
+pub fn saveCtx(filename: &str, ctx: &GameContext) -> io::Result<()> {
+	let inner = unsafe {InnerGameContext::fromGameContext(ctx)};
+	serde_json::to_writer(File::create(filename)?, &inner)?;
+	Ok(())
+}
+
+fn snapshotCtx(ctx: &GameContext) -> Option<String> {
+	let inner = unsafe {InnerGameContext::fromGameContext(ctx)};
+	serde_json::to_string(&inner).map_err(|e| eprintln!("Warning: Could not snapshot the room: {}", e)).ok()
+}
+
+/// Rebuilds a level from `GameManager::getRoomSnapshot` or `Headless::getRoomSnapshot`.
+pub fn loadCtxSnapshot<'a, C>(snapshot: &str, tileset: &str, creator: &'a TextureCreator<C>) -> io::Result<GameContext<'a>> {
+	let ctx: InnerGameContext = serde_json::from_str(snapshot)?;
+	ctx.intoGameContext(tileset, creator)
+}
+
+pub fn loadCtx<'a, C>(filename: &str, creator: &'a TextureCreator<C>) -> io::Result<GameContext<'a>> {
+	loadCtxTileset(filename, DEFAULT_TILESET, creator)
+}
+
+pub fn loadCtxTileset<'a, C>(filename: &str, tileset: &str, creator: &'a TextureCreator<C>) -> io::Result<GameContext<'a>> {
 	let mut deserializer = Deserializer::from_reader(File::open(filename)?);
 	let ctx = InnerGameContext::deserialize(&mut deserializer)?;
 
-	ctx.intoGameContext(creator)
+	ctx.intoGameContext(tileset, creator)
 }
 
 #[repr(u8)]
-#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum Direction {
 	Up,
 	Down,
@@ -1,14 +1,21 @@
 #![allow(non_snake_case)]
 extern crate sdl2;
 
-use NinjaDungeon::{GameManager, GameContext, Player, loadCtx};
+use NinjaDungeon::{GameManager, GameContext, GameRng, GameState, Replay, ReplayRecorder, ReplayPlayer, SaveRequest, loadCtxTileset, loadCtxSnapshot, saveCtx};
+use NinjaDungeon::{Campaign, LevelEntry, SoundManager};
+use NinjaDungeon::{SlotInfo, SlotSelect, SLOT_COUNT, slotSaveFile, hasSaveFile, clearSaveFile};
 use NinjaDungeon::PO;
+#[cfg(feature = "scripting")]
+use NinjaDungeon::Scripts;
 
 use sdl2::pixels::Color;
 use sdl2::image::LoadTexture;
-use sdl2::render::Texture;
+use sdl2::render::TextureCreator;
+use sdl2::video::WindowContext;
 
-use std::fs;
+use rand::RngCore;
+
+use std::time::Duration;
 
 use std::cell::UnsafeCell;
 
@@ -17,31 +24,259 @@ const HEIGHT: u32 = 12*50;
 
 const NAME: &str = "Ninja Dungeon";
 
+const CAMPAIGN: &str = "Resources/Campaign.json";
+
+const FONT: &str = "Resources/Font/Symbola_hint.ttf";
+
+const KEYS: &str = "Resources/Keys.json";
+
 const COLOR: Color = Color::RGB(0x88, 0x88, 0x88);
 
 fn main() 
 {
 
-	let (mut manager, creator) = GameManager::initialize(NAME, WIDTH, HEIGHT, COLOR);
+	let ttfContext = sdl2::ttf::init().map_err(|e| e.to_string());
+	let (manager, creator) = GameManager::initialize(NAME, WIDTH, HEIGHT, COLOR);
+	// The font and HUD borrow `creator`, so the manager has to be dropped before it
+	let mut manager = manager;
+	manager.setInterpolation(std::env::args().any(|arg| arg == "--interpolate"));
+	if let Some(steps) = argValue("--transition-steps") {
+		manager.setTransitionSteps(steps.parse().expect("--transition-steps takes an unsigned integer"));
+	}
+
+	let campaign = Campaign::load(&argValue("--campaign").unwrap_or_else(|| CAMPAIGN.to_string())).expect("Could not read campaign");
+
+	manager.loadKeyBindings(&argValue("--keys").unwrap_or_else(|| KEYS.to_string()));
+	if let Err(e) = ttfContext.as_ref().map_err(String::clone).and_then(|ttf| manager.loadFont(ttf, &creator, FONT, 20)) {
+		eprintln!("Could not load font: {}", e);
+	}
+	manager.loadHud(&creator).expect("Could not load HUD sprites");
+
+	if !std::env::args().any(|arg| arg == "--no-audio") {
+		manager.setSoundManager(SoundManager::new());
+	}
+
+	let replay = argValue("--replay").map(|filename| Replay::load(&filename).expect("Could not read replay"));
 
-	let mut currentMap = match fs::read_to_string("Resources/CurrentMap").unwrap().trim() {
-		"0" => 0,
-		"1" => 1,
-		_ => panic!("Too high of a map id"),
+	if replay.is_none() {
+		manager.setState(GameState::Title);
+		while manager.showScreen(None) {}
+		if manager.hasQuit() {return;}
+	}
+
+	// Replays bring their own map and never touch the save slots
+	let slot = if replay.is_some() {None}
+	else {
+		match argValue("--slot") {
+			Some(slot) => Some(slot.parse::<usize>().ok().filter(|slot| (1..=SLOT_COUNT).contains(slot)).expect("--slot takes a slot number") - 1),
+			None => {
+				let mut select = SlotSelect::new();
+				loop {
+					match manager.selectSlot(&mut select) {
+						Ok(true) => (),
+						Ok(false) => break,
+						Err(e) => {
+							eprintln!("Could not show the save slots, pick one with --slot instead: {}", e);
+							return;
+						},
+					}
+				}
+				match select.getChoice() {
+					Some(slot) => Some(slot),
+					None => return,
+				}
+			},
+		}
+	};
+	let mut info = slot.map(|slot| SlotInfo::load(slot).unwrap_or_else(|_| SlotInfo::new(format!("Slot {}", slot + 1))));
+	let mut basePlayTime = info.as_ref().map_or(0, |info| info.playTime);
+
+	// A slot that has won every level only has the credits left to show
+	if info.as_ref().map_or(false, |info| info.level >= campaign.len()) {
+		showCredits(&mut manager, &creator);
+		return;
+	}
+
+	let level = match (&replay, &info) {
+		(Some(replay), _) => campaign.findLevel(&replay.map).cloned().unwrap_or_else(|| LevelEntry {
+			name: replay.map.clone(),
+			file: replay.map.clone(),
+			tileset: None,
+			music: None,
+			script: None,
+		}),
+		(None, Some(info)) => campaign.getLevel(info.level).or(campaign.getLevel(0)).unwrap().clone(),
+		(None, None) => unreachable!(),
+	};
+	let saveFile = slot.filter(|slot| hasSaveFile(*slot)).map(slotSaveFile);
+	let mut ctx = match saveFile {
+		Some(ref saveFile) => loadCtxTileset(saveFile, level.getTileset(), &creator).expect("Could not read save file"),
+		None => loadCtxTileset(&level.file, level.getTileset(), &creator).unwrap(),
 	};
 
-	
-	let ctx = if currentMap == 0 {loadCtx("Resources/Map1.mp", &creator).unwrap()}
-	else {loadCtx("Resources/Map2.mp", &creator).unwrap()};
+	// A save carries the generator state it was made with
+	if saveFile.is_none() {
+		let seed = match replay {
+			Some(ref replay) => replay.seed,
+			None => argValue("--seed").map(|seed| seed.parse::<u64>().expect("--seed takes an unsigned integer"))
+				.unwrap_or_else(|| GameRng::fromEntropy().getSeed()),
+		};
+		ctx.setSeed(seed);
+	}
+	let seed = ctx.getSeed();
+	// On stderr so the seed of an unlucky run can be passed to --seed again
+	eprintln!("Seed: {}", seed);
+
+	let mut recordFile = argValue("--record");
+	// A replay always starts from the map file, which a save has moved on from
+	if recordFile.is_some() && saveFile.is_some() {
+		eprintln!("Warning: --record is ignored when continuing from a save");
+		recordFile = None;
+	}
+	if recordFile.is_some() {
+		manager.startRecording(ReplayRecorder::new(seed, &level.file));
+	}
+	let replaying = replay.is_some();
+	if let Some(replay) = replay {
+		manager.startPlayback(ReplayPlayer::new(replay));
+	}
 
+	let mut level = level;
 	let mut po = UnsafeCell::new(PO::new(ctx));
+	manager.setState(GameState::Playing);
+
+	loop {
+		manager.getSoundManager().playMusic(level.music.as_deref());
+		loadScripts(&mut manager, &level, &mut po);
+
+		loop {
+			while manager.mainLoop(&mut po) {}
+			// Dying sends the player back to the room entrance or last checkpoint. Replays end instead.
+			if manager.getState() == GameState::GameOver && !replaying {
+				manager.resume();
+				while manager.showScreen(None) {}
+				if manager.hasQuit() {break;}
+				match restoreRoom(&manager, &level, &creator) {
+					Some(ctx) => replacePO(&mut manager, &level, &mut po, ctx, &mut recordFile),
+					None => break,
+				}
+				manager.setState(GameState::Playing);
+				continue;
+			}
+			match (manager.takeSaveRequest(), slot, info.as_mut()) {
+				(Some(SaveRequest::Save), Some(slot), Some(info)) => {
+					info.update(po.get_mut().getCtx(), Duration::from_secs(basePlayTime) + manager.getPlayTime());
+					if let Err(e) = saveCtx(&slotSaveFile(slot), po.get_mut().getCtx()).and_then(|_| info.save(slot)) {
+						eprintln!("Could not save: {}", e);
+					}
+				},
+				(Some(SaveRequest::Load), Some(slot), Some(info)) => match SlotInfo::load(slot) {
+					Ok(saved) => {
+						let savedLevel = campaign.getLevel(saved.level).cloned().unwrap_or_else(|| level.clone());
+						match loadCtxTileset(&slotSaveFile(slot), savedLevel.getTileset(), &creator) {
+							Ok(ctx) => {
+								level = savedLevel;
+								manager.getSoundManager().playMusic(level.music.as_deref());
+								replacePO(&mut manager, &level, &mut po, ctx, &mut recordFile);
+								*info = saved;
+								basePlayTime = info.playTime;
+								manager.resetPlayTime();
+								manager.resetRoomSnapshot();
+							},
+							Err(e) => eprintln!("Could not load: {}", e),
+						}
+					},
+					Err(e) => eprintln!("Could not load: {}", e),
+				},
+				(Some(SaveRequest::RestartRoom), ..) => if let Some(ctx) = restoreRoom(&manager, &level, &creator) {
+					replacePO(&mut manager, &level, &mut po, ctx, &mut recordFile);
+				},
+				(Some(_), ..) => (),
+				(None, ..) => break,
+			}
+		}
+
+		// A replay covers a single level
+		saveRecording(&mut manager, &mut recordFile);
+		// Anything but a won level means the player quit, closed the window or a replay ended
+		if replaying || manager.getState() != GameState::LevelComplete {break;}
+
+		let next = info.as_ref().map_or(campaign.len(), |info| info.level + 1);
+		if let (Some(slot), Some(info)) = (slot, info.as_mut()) {
+			info.update(po.get_mut().getCtx(), Duration::from_secs(basePlayTime) + manager.getPlayTime());
+			// Past the last level marks the slot as finished
+			info.level = next;
+			if let Err(e) = clearSaveFile(slot).and_then(|_| info.save(slot)) {
+				eprintln!("Could not record progress: {}", e);
+			}
+		}
 
-	while manager.mainLoop(&mut po) {}
-	if manager.advance {
-		fs::write("Resources/CurrentMap", if currentMap == 0 {"1"} else {"0"}).unwrap();
-	let conglaturations = creator.load_texture("Resources/Images/Conglaturations.png").unwrap();
-	while manager.conglaturate(&conglaturations) {}
+		manager.resume();
+		match campaign.getLevel(next) {
+			Some(entry) => {
+				while manager.showScreen(None) {}
+				if manager.hasQuit() {break;}
+				level = entry.clone();
+				let mut ctx = loadCtxTileset(&level.file, level.getTileset(), &creator).unwrap();
+				ctx.setSeed(po.get_mut().getRng().next_u64());
+				po = UnsafeCell::new(PO::new(ctx));
+				manager.resetRoomSnapshot();
+				manager.setState(GameState::Playing);
+			},
+			None => {
+				showCredits(&mut manager, &creator);
+				break;
+			},
+		}
 	}
 
 }
 
+fn showCredits(manager: &mut GameManager, creator: &TextureCreator<WindowContext>) {
+	let conglaturations = creator.load_texture("Resources/Images/Conglaturations.png")
+		.map_err(|e| eprintln!("Warning: Could not load the credits image: {}", e)).ok();
+	manager.setState(GameState::Credits);
+	while manager.showScreen(conglaturations.as_ref()) {}
+}
+
+fn restoreRoom<'a>(manager: &GameManager, level: &LevelEntry, creator: &'a TextureCreator<WindowContext>) -> Option<GameContext<'a>> {
+	let snapshot = manager.getRoomSnapshot()?;
+	loadCtxSnapshot(snapshot, level.getTileset(), creator).map_err(|e| eprintln!("Could not restore the room: {}", e)).ok()
+}
+
+/// Swaps in another state of the level. Its script is loaded again so globals set by the old state,
+/// such as a door being open, do not carry over. A replay cannot follow the swap, so any recording
+/// ends here.
+fn replacePO<'a>(manager: &mut GameManager, level: &LevelEntry, po: &mut UnsafeCell<PO<'a>>, ctx: GameContext<'a>, recordFile: &mut Option<String>) {
+	saveRecording(manager, recordFile);
+	*po = UnsafeCell::new(PO::new(ctx));
+	loadScripts(manager, level, po);
+}
+
+fn saveRecording(manager: &mut GameManager, recordFile: &mut Option<String>) {
+	if let (Some(filename), Some(recorder)) = (recordFile.take(), manager.stopRecording()) {
+		if let Err(e) = recorder.getReplay().save(&filename) {
+			eprintln!("Could not save the replay: {}", e);
+		}
+	}
+}
+
+#[cfg(feature = "scripting")]
+fn loadScripts(manager: &mut GameManager, level: &LevelEntry, po: &mut UnsafeCell<PO>) {
+	let scripts = level.script.as_ref().and_then(|filename| Scripts::load(filename)
+		.map_err(|e| eprintln!("Warning: Could not load \"{}\": {}", filename, e)).ok());
+	manager.setScripts(scripts, po);
+}
+
+#[cfg(not(feature = "scripting"))]
+fn loadScripts(_manager: &mut GameManager, level: &LevelEntry, _po: &mut UnsafeCell<PO>) {
+	if let Some(ref filename) = level.script {
+		eprintln!("Warning: \"{}\" is ignored without the scripting feature", filename);
+	}
+}
+
+fn argValue(name: &str) -> Option<String> {
+	let mut args = std::env::args().skip_while(|arg| arg != name);
+	args.next()?;
+	args.next()
+}
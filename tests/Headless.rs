@@ -0,0 +1,68 @@
+#![allow(non_snake_case)]
+extern crate NinjaDungeon;
+extern crate sdl2;
+
+use NinjaDungeon::{Headless, PO, Signals, DrawRecorder, DrawCall, loadCtx};
+
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+
+use std::cell::UnsafeCell;
+
+const MAP: &str = "Resources/Map1.mp";
+const SEED: u64 = 1234;
+
+/// Where the player's sprite was drawn in the recorded frame
+fn playerRect(recorder: &DrawRecorder) -> Rect {
+	recorder.getCalls().iter().find_map(|call| match call {
+		DrawCall::Sprite {sprite, rect, ..} if sprite.contains("Ninja_") => Some(*rect),
+		_ => None,
+	}).expect("The player was not drawn")
+}
+
+/// Steps a fresh copy of Map1 with `inputs`, one per step, and records the last frame.
+fn run(inputs: &[Signals]) -> DrawRecorder {
+	let (mut headless, creator) = Headless::initialize(17*50, 12*50, Color::BLACK).unwrap();
+	let mut ctx = loadCtx(MAP, &creator).unwrap();
+	ctx.setSeed(SEED);
+	let mut po = UnsafeCell::new(PO::new(ctx));
+	for &signals in inputs {
+		assert!(headless.step(&mut po, signals));
+	}
+	assert_eq!(headless.getFrame(), inputs.len() as u64);
+	let mut recorder = DrawRecorder::new();
+	headless.renderTo(&mut po, &mut recorder);
+	recorder
+}
+
+#[test]
+fn walkingMovesThePlayerSprite() {
+	let start = playerRect(&run(&[]));
+	let mut inputs = vec![Signals {down: Some(true), ..Signals::default()}];
+	inputs.extend(std::iter::repeat(Signals::default()).take(19));
+	let moved = playerRect(&run(&inputs));
+	assert_eq!(moved.x(), start.x());
+	assert!(moved.y() > start.y());
+}
+
+#[test]
+fn releasingStopsThePlayer() {
+	let mut inputs = vec![Signals {down: Some(true), ..Signals::default()}];
+	inputs.extend(std::iter::repeat(Signals::default()).take(9));
+	inputs.push(Signals {down: Some(false), ..Signals::default()});
+	let stopped = playerRect(&run(&inputs));
+	inputs.extend(std::iter::repeat(Signals::default()).take(10));
+	assert_eq!(playerRect(&run(&inputs)), stopped);
+}
+
+#[test]
+fn sameInputsDrawTheSameFrame() {
+	let inputs: Vec<Signals> = (0..60).map(|step| match step {
+		0 => Signals {down: Some(true), ..Signals::default()},
+		30 => Signals {down: Some(false), attack: Some(true), attackPressed: true, ..Signals::default()},
+		_ => Signals::default(),
+	}).collect();
+	let first = run(&inputs);
+	assert!(!first.getCalls().is_empty());
+	assert_eq!(first.getCalls(), run(&inputs).getCalls());
+}
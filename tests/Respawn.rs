@@ -0,0 +1,39 @@
+#![allow(non_snake_case)]
+extern crate NinjaDungeon;
+extern crate sdl2;
+
+use NinjaDungeon::{Headless, PO, Signals, DEFAULT_TILESET, loadCtx, loadCtxSnapshot};
+
+use sdl2::pixels::Color;
+
+use std::cell::UnsafeCell;
+
+const MAP: &str = "Resources/Map1.mp";
+
+#[test]
+fn dyingRestoresTheRoomSnapshot() {
+	let (mut headless, creator) = Headless::initialize(17*50, 12*50, Color::BLACK).unwrap();
+	let mut po = UnsafeCell::new(PO::new(loadCtx(MAP, &creator).unwrap()));
+	let start = po.get_mut().getCtx().getPlayer().getPosition();
+	let health = po.get_mut().getCtx().getPlayer().getHealth();
+
+	// The corridor from the start leads down
+	let down = Signals {down: Some(true), ..Signals::default()};
+	for _ in 0..20 {
+		assert!(headless.step(&mut po, down));
+	}
+	assert!(po.get_mut().getCtx().getPlayer().getPosition().1 > start.1);
+
+	po.get_mut().die();
+	assert!(!headless.step(&mut po, Signals::default()));
+	assert!(!headless.advance);
+
+	// What main does on game over: carry on from the snapshot taken on entering the room
+	headless.resume();
+	let snapshot = headless.getRoomSnapshot().expect("No snapshot was taken").to_string();
+	let mut po = UnsafeCell::new(PO::new(loadCtxSnapshot(&snapshot, DEFAULT_TILESET, &creator).unwrap()));
+	let player = po.get_mut().getCtx().getPlayer();
+	assert_eq!((player.getPosition().0, player.getPosition().1), (start.0, start.1));
+	assert_eq!(player.getHealth(), health);
+	assert!(headless.step(&mut po, Signals::default()));
+}